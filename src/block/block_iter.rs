@@ -0,0 +1,353 @@
+use alloc::vec::Vec;
+
+use crate::DecodeError;
+use crate::Marked;
+use crate::SeqMarked;
+use crate::block::Block;
+use crate::block::NORMAL;
+use crate::block::TOMBSTONE;
+use crate::codec::Reader;
+use crate::codec::unzigzag;
+
+/// An iterator over the entries of a [`Block`], in key order.
+///
+/// The iteration ends after the first error.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct BlockIter<B> {
+    block: Block<B>,
+
+    /// The position of the next entry to decode.
+    offset: usize,
+
+    /// The index of the next restart point to reach.
+    next_restart: usize,
+
+    /// The key and seq of the last decoded entry, to decode the next one.
+    key: Vec<u8>,
+    seq: u64,
+
+    /// An entry decoded by [`seek()`](Self::seek) and not returned yet.
+    peeked: Option<(Vec<u8>, SeqMarked)>,
+
+    done: bool,
+}
+
+impl<B> BlockIter<B>
+where B: AsRef<[u8]>
+{
+    pub(crate) fn new(block: Block<B>) -> Self {
+        Self {
+            block,
+            offset: 0,
+            next_restart: 0,
+            key: Vec::new(),
+            seq: 0,
+            peeked: None,
+            done: false,
+        }
+    }
+
+    /// Positions the iterator at the first entry whose key is greater than or equal to `key`,
+    /// i.e., the newest version of `key` if it is present.
+    ///
+    /// It binary searches the restart points, then scans at most one restart interval.
+    pub fn seek(&mut self, key: &[u8]) -> Result<(), DecodeError> {
+        self.peeked = None;
+        self.done = false;
+
+        let res = self.seek_inner(key);
+        if res.is_err() {
+            self.done = true;
+        }
+        res
+    }
+
+    fn seek_inner(&mut self, key: &[u8]) -> Result<(), DecodeError> {
+        // Find the first restart point whose key is greater than or equal to `key`.
+        let (mut lo, mut hi) = (0, self.block.num_restarts);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.restart_key(mid)? < key {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        // The target is in the interval before it.
+        let i = lo.saturating_sub(1);
+        self.offset = if self.block.num_restarts == 0 {
+            0
+        } else {
+            self.block.restart(i)
+        };
+        self.next_restart = i;
+
+        while let Some((k, v)) = self.decode_next()? {
+            if k.as_slice() >= key {
+                self.peeked = Some((k, v));
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the key of the `i`-th restart point, which is stored in full.
+    fn restart_key(&self, i: usize) -> Result<&[u8], DecodeError> {
+        let entries = self.block.entries();
+        let mut r = Reader::new(&entries[self.block.restart(i)..]);
+
+        let shared = r.take_varint()?;
+        if shared != 0 {
+            return Err(DecodeError::InvalidLength {
+                expected: 0,
+                actual: shared as usize,
+            });
+        }
+
+        let unshared = r.take_varint()? as usize;
+        r.take(unshared)
+    }
+
+    /// Decodes the entry at `offset`; returns `Ok(None)` at the end of the block.
+    fn decode_next(&mut self) -> Result<Option<(Vec<u8>, SeqMarked)>, DecodeError> {
+        let entries = self.block.entries();
+        if self.offset >= entries.len() {
+            return Ok(None);
+        }
+
+        // A restart point does not depend on the previous entry.
+        if self.next_restart < self.block.num_restarts
+            && self.block.restart(self.next_restart) == self.offset
+        {
+            self.key.clear();
+            self.seq = 0;
+            self.next_restart += 1;
+        }
+
+        let mut r = Reader::new(&entries[self.offset..]);
+
+        let shared = r.take_varint()? as usize;
+        if shared > self.key.len() {
+            return Err(DecodeError::InvalidLength {
+                expected: self.key.len(),
+                actual: shared,
+            });
+        }
+        let unshared = r.take_varint()? as usize;
+        let suffix = r.take(unshared)?;
+
+        let delta = unzigzag(r.take_varint()?);
+        let marked = match r.take_array()? {
+            [NORMAL] => {
+                let len = r.take_varint()? as usize;
+                Marked::Normal(r.take(len)?.to_vec())
+            }
+            [TOMBSTONE] => Marked::TombStone,
+            [b] => return Err(DecodeError::InvalidMark(b)),
+        };
+
+        self.key.truncate(shared);
+        self.key.extend_from_slice(suffix);
+        self.seq = self.seq.wrapping_add(delta as u64);
+        self.offset = entries.len() - r.remaining().len();
+
+        Ok(Some((self.key.clone(), SeqMarked::new(self.seq, marked))))
+    }
+}
+
+impl<B> Iterator for BlockIter<B>
+where B: AsRef<[u8]>
+{
+    type Item = Result<(Vec<u8>, SeqMarked), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(entry) = self.peeked.take() {
+            return Some(Ok(entry));
+        }
+
+        if self.done {
+            return None;
+        }
+
+        let res = self.decode_next().transpose();
+        if !matches!(res, Some(Ok(_))) {
+            self.done = true;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::vec;
+
+    use super::*;
+    use crate::InternalSeq;
+    use crate::block::BlockBuilder;
+
+    fn key(i: u64) -> Vec<u8> {
+        format!("prefix/key-{:04}", i).into_bytes()
+    }
+
+    /// Build entries of keys `0, 2, 4, ..., 198`, with seqs around `1000`, every third one is a
+    /// tombstone.
+    fn sample() -> Vec<(Vec<u8>, SeqMarked)> {
+        (0..100)
+            .map(|i| {
+                let seq = if i % 2 == 0 { 1000 + i } else { 1000 - i };
+                let v = if i % 3 == 0 {
+                    SeqMarked::new_tombstone(seq)
+                } else {
+                    SeqMarked::new_normal(seq, format!("v{}", i).into_bytes())
+                };
+                (key(i * 2), v)
+            })
+            .collect()
+    }
+
+    fn build(entries: &[(Vec<u8>, SeqMarked)], restart_interval: usize) -> Block {
+        let mut b = BlockBuilder::new().with_restart_interval(restart_interval);
+        for (k, v) in entries {
+            b.add(k, v.as_ref().map(|d| d.as_slice()));
+        }
+        Block::new(b.finish()).unwrap()
+    }
+
+    #[test]
+    fn test_iter() {
+        let entries = sample();
+
+        for interval in [1, 2, 16, 1000] {
+            let block = build(&entries, interval);
+            assert_eq!(block.num_restarts(), 100usize.div_ceil(interval));
+
+            let got = block.iter().collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(got, entries);
+
+            let got = block.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(got, entries);
+        }
+    }
+
+    #[test]
+    fn test_prefix_compression() {
+        let entries = sample();
+
+        // With a restart at every entry, keys and seqs are stored in full.
+        let full = build(&entries, 1).into_inner().len();
+        let compressed = build(&entries, 16).into_inner().len();
+
+        // Keys share at least 11 bytes.
+        assert!(compressed + 100 * 8 < full, "{} vs {}", compressed, full);
+    }
+
+    #[test]
+    fn test_seek() {
+        let entries = sample();
+
+        for interval in [1, 3, 16, 1000] {
+            let block = build(&entries, interval);
+            let mut it = block.iter();
+
+            for i in 0..=200 {
+                it.seek(&key(i)).unwrap();
+
+                let want = entries.iter().find(|(k, _)| k >= &key(i));
+                assert_eq!(it.next().map(|r| r.unwrap()).as_ref(), want, "seek {}", i);
+
+                // Continues after the found entry.
+                let pos = entries.iter().position(|(k, _)| k >= &key(i));
+                let want_next = pos.and_then(|p| entries.get(p + 1));
+                assert_eq!(it.next().map(|r| r.unwrap()).as_ref(), want_next);
+            }
+
+            it.seek(b"").unwrap();
+            assert_eq!(it.count(), 100);
+
+            let mut it = block.iter();
+            it.seek(b"z").unwrap();
+            assert!(it.next().is_none());
+        }
+    }
+
+    #[test]
+    fn test_seek_versions() {
+        // 3 versions of every key, newest first.
+        let entries = (0..20)
+            .flat_map(|i| {
+                (0..3).rev().map(move |v| (key(i), SeqMarked::new_normal(i * 10 + v, vec![])))
+            })
+            .collect::<Vec<_>>();
+
+        for interval in [1, 2, 4, 16] {
+            let block = build(&entries, interval);
+
+            let got = block.iter().collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(got, entries);
+
+            let mut it = block.iter();
+            for i in 0..20 {
+                it.seek(&key(i)).unwrap();
+                for v in (0..3).rev() {
+                    let (k, got) = it.next().unwrap().unwrap();
+                    assert_eq!(k, key(i));
+                    assert_eq!(got.internal_seq(), InternalSeq::new(i * 10 + v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_seek_empty() {
+        let block = Block::new(BlockBuilder::new().finish()).unwrap();
+        let mut it = block.iter();
+        it.seek(b"a").unwrap();
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_corrupted() {
+        let mut b = BlockBuilder::new();
+        b.add(b"a", SeqMarked::new_normal(1, b"x".as_slice()));
+        b.add(b"b", SeqMarked::new_tombstone(2));
+        let mut buf = b.finish();
+
+        // Invalid mark of the 2nd entry.
+        buf[11] = 2;
+        let block = Block::new(buf.clone()).unwrap();
+        let mut it = block.iter();
+        assert_eq!(
+            it.next(),
+            Some(Ok((b"a".to_vec(), SeqMarked::new_normal(1, b"x".to_vec()))))
+        );
+        assert_eq!(it.next(), Some(Err(DecodeError::InvalidMark(2))));
+        assert_eq!(it.next(), None);
+
+        // Shared prefix longer than the previous key.
+        buf[11] = 1;
+        buf[7] = 2;
+        let block = Block::new(buf).unwrap();
+        let got = block.iter().collect::<Vec<_>>();
+        assert_eq!(
+            got[1],
+            Err(DecodeError::InvalidLength {
+                expected: 1,
+                actual: 2
+            })
+        );
+
+        // Restart key not stored in full.
+        let block = Block::new(vec![1, 0, 0, 0, 0, 0, 0, 0, 1]).unwrap();
+        assert_eq!(
+            block.iter().seek(b"a"),
+            Err(DecodeError::InvalidLength {
+                expected: 0,
+                actual: 1
+            })
+        );
+    }
+}
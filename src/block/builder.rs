@@ -0,0 +1,236 @@
+use alloc::vec::Vec;
+
+use crate::Marked;
+use crate::SeqMarked;
+use crate::block::NORMAL;
+use crate::block::TOMBSTONE;
+use crate::block::U32_LEN;
+use crate::codec::MAX_VARINT_LEN;
+use crate::codec::put_varint;
+use crate::codec::zigzag;
+
+/// Builds a prefix-compressed [`Block`](crate::block::Block) from entries in the order of
+/// [`InternalKey`](crate::InternalKey): sorted by key, then by order key descending, so that
+/// several versions of a key are stored newest first.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct BlockBuilder {
+    restart_interval: usize,
+
+    /// The encoded entries.
+    buf: Vec<u8>,
+
+    /// The positions of restart points.
+    restarts: Vec<u32>,
+
+    /// The number of entries added.
+    len: usize,
+
+    last_key: Vec<u8>,
+    last_order_key: SeqMarked<()>,
+}
+
+impl Default for BlockBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockBuilder {
+    /// The default number of entries between restart points.
+    pub const DEFAULT_RESTART_INTERVAL: usize = 16;
+
+    pub fn new() -> Self {
+        Self {
+            restart_interval: Self::DEFAULT_RESTART_INTERVAL,
+            buf: Vec::new(),
+            restarts: Vec::new(),
+            len: 0,
+            last_key: Vec::new(),
+            last_order_key: SeqMarked::new_not_found(),
+        }
+    }
+
+    /// Sets the number of entries between restart points; `0` is treated as `1`.
+    ///
+    /// A smaller interval makes seeking faster, and the block larger.
+    #[must_use]
+    pub fn with_restart_interval(mut self, restart_interval: usize) -> Self {
+        self.restart_interval = restart_interval.max(1);
+        self
+    }
+
+    /// Returns the number of entries added.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the length of the block if finished now.
+    pub fn estimated_len(&self) -> usize {
+        self.buf.len() + (self.restarts.len() + 1) * U32_LEN
+    }
+
+    /// Returns an upper bound of the bytes [`add()`](Self::add) grows the block by for an entry,
+    /// including its restart position.
+    pub fn max_entry_len(key: &[u8], value: &SeqMarked<&[u8]>) -> usize {
+        // shared, unshared, seq delta, mark, data_len.
+        let header = 3 * MAX_VARINT_LEN + 1 + MAX_VARINT_LEN;
+        let data_len = value.data_ref().map_or(0, |d| d.len());
+        header + key.len() + data_len + U32_LEN
+    }
+
+    /// Adds an entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entry is not after the previous one in the order of
+    /// [`InternalKey`](crate::InternalKey), or the block exceeds `u32::MAX` bytes, which a caller
+    /// can rule out with [`estimated_len()`](Self::estimated_len) and
+    /// [`max_entry_len()`](Self::max_entry_len).
+    pub fn add(&mut self, key: &[u8], value: SeqMarked<&[u8]>) {
+        let order_key = value.order_key();
+        assert!(
+            self.is_empty()
+                || key > self.last_key.as_slice()
+                || (key == self.last_key.as_slice() && order_key < self.last_order_key),
+            "block entries must be in InternalKey order: {:?}@{:?} after {:?}@{:?}",
+            key,
+            order_key,
+            self.last_key,
+            self.last_order_key
+        );
+
+        let (seq, marked) = value.into_parts();
+
+        let (shared, base_seq) = if self.len % self.restart_interval == 0 {
+            let offset = u32::try_from(self.buf.len()).expect("block exceeds u32::MAX");
+            self.restarts.push(offset);
+            (0, 0)
+        } else {
+            let shared = self.last_key.iter().zip(key).take_while(|(a, b)| a == b).count();
+            (shared, *self.last_order_key.internal_seq())
+        };
+
+        put_varint(&mut self.buf, shared as u64);
+        put_varint(&mut self.buf, (key.len() - shared) as u64);
+        self.buf.extend_from_slice(&key[shared..]);
+        put_varint(&mut self.buf, zigzag(seq.wrapping_sub(base_seq) as i64));
+
+        match marked {
+            Marked::Normal(d) => {
+                self.buf.push(NORMAL);
+                put_varint(&mut self.buf, d.len() as u64);
+                self.buf.extend_from_slice(d);
+            }
+            Marked::TombStone => self.buf.push(TOMBSTONE),
+        }
+
+        self.last_key.clear();
+        self.last_key.extend_from_slice(key);
+        self.last_order_key = order_key;
+        self.len += 1;
+    }
+
+    /// Appends the restart array and returns the block.
+    pub fn finish(mut self) -> Vec<u8> {
+        for r in &self.restarts {
+            self.buf.extend_from_slice(&r.to_be_bytes());
+        }
+        let num_restarts = self.restarts.len() as u32;
+        self.buf.extend_from_slice(&num_restarts.to_be_bytes());
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let mut b = BlockBuilder::new().with_restart_interval(2);
+        b.add(b"abc", SeqMarked::new_normal(10, b"x".as_slice()));
+        b.add(b"abd", SeqMarked::new_tombstone(8));
+        b.add(b"b", SeqMarked::new_normal(300, b"".as_slice()));
+
+        assert_eq!(b.len(), 3);
+        assert_eq!(b.estimated_len(), 33);
+
+        assert_eq!(b.finish(), vec![
+            0, 3, b'a', b'b', b'c', 20, 0, 1, b'x', // restart: full key, seq 10
+            2, 1, b'd', 3, 1, // shared "ab", seq delta -2
+            0, 1, b'b', 0xd8, 0x04, 0, 0, // restart: full key, seq 300
+            0, 0, 0, 0, // restart 0
+            0, 0, 0, 14, // restart 1
+            0, 0, 0, 2, // num_restarts
+        ]);
+    }
+
+    #[test]
+    fn test_max_entry_len() {
+        let entries = [
+            (
+                b"abc".as_slice(),
+                SeqMarked::new_normal(10, b"x".as_slice()),
+            ),
+            (b"abd", SeqMarked::new_tombstone(u64::MAX)),
+            (b"b", SeqMarked::new_normal(300, [0u8; 200].as_slice())),
+        ];
+
+        let mut b = BlockBuilder::new().with_restart_interval(2);
+        for (key, value) in entries {
+            let before = b.estimated_len();
+            let bound = BlockBuilder::max_entry_len(key, &value);
+            b.add(key, value);
+            assert!(b.estimated_len() - before <= bound);
+        }
+    }
+
+    #[test]
+    fn test_restart_interval() {
+        let mut b = BlockBuilder::default().with_restart_interval(0);
+        assert!(b.is_empty());
+
+        b.add(b"a", SeqMarked::new_tombstone(1));
+        b.add(b"b", SeqMarked::new_tombstone(2));
+        assert_eq!(b.restarts, vec![0, 5]);
+    }
+
+    #[test]
+    fn test_versions() {
+        let mut b = BlockBuilder::new();
+        b.add(b"a", SeqMarked::new_tombstone(3));
+        b.add(b"a", SeqMarked::new_normal(3, b"x".as_slice()));
+        b.add(b"a", SeqMarked::new_normal(1, b"y".as_slice()));
+        b.add(b"b", SeqMarked::new_normal(2, b"z".as_slice()));
+        assert_eq!(b.len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "block entries must be in InternalKey order")]
+    fn test_unsorted_key() {
+        let mut b = BlockBuilder::new();
+        b.add(b"b", SeqMarked::new_tombstone(1));
+        b.add(b"a", SeqMarked::new_tombstone(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "block entries must be in InternalKey order")]
+    fn test_unsorted_seq() {
+        let mut b = BlockBuilder::new();
+        b.add(b"b", SeqMarked::new_tombstone(1));
+        b.add(b"b", SeqMarked::new_tombstone(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "block entries must be in InternalKey order")]
+    fn test_duplicate() {
+        let mut b = BlockBuilder::new();
+        b.add(b"b", SeqMarked::new_tombstone(1));
+        b.add(b"b", SeqMarked::new_tombstone(1));
+    }
+}
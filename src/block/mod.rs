@@ -0,0 +1,202 @@
+//! Prefix-compressed blocks of `(key, SeqMarked<Vec<u8>>)` entries, a building block of on-disk
+//! formats.
+//!
+//! A block is built by [`BlockBuilder`] from entries in the order of [`InternalKey`], i.e., sorted
+//! by key, then newest version first, and read by [`Block`]:
+//!
+//! ```text
+//! | entry | ... | restart: u32 | ... | num_restarts: u32 |
+//! ```
+//!
+//! An entry is:
+//!
+//! ```text
+//! | shared: varint | unshared: varint | key suffix | seq delta: varint | mark: u8 | [data_len: varint | data] |
+//! ```
+//!
+//! - `shared` is the length of the prefix shared with the key of the previous entry, and only the
+//!   rest of the key is stored.
+//! - `seq delta` is the zigzag-encoded difference from the seq of the previous entry.
+//! - `mark` is `0` for normal, followed by the data, or `1` for tombstone.
+//!
+//! Every [`restart interval`](BlockBuilder::with_restart_interval) entries, an entry is a restart
+//! point: it stores the full key and the full seq, so that decoding can start from it. The
+//! big-endian `u32` positions of restart points at the end of a block are used to
+//! [`seek()`](BlockIter::seek) by binary search.
+//!
+//! ```rust
+//! use seq_marked::SeqMarked;
+//! use seq_marked::block::Block;
+//! use seq_marked::block::BlockBuilder;
+//!
+//! let mut b = BlockBuilder::new();
+//! b.add(b"user/1", SeqMarked::new_normal(10, b"foo".as_slice()));
+//! b.add(b"user/2", SeqMarked::new_tombstone(11));
+//! b.add(b"user/3", SeqMarked::new_normal(9, b"bar".as_slice()));
+//!
+//! let block = Block::new(b.finish())?;
+//!
+//! let mut it = block.iter();
+//! it.seek(b"user/2")?;
+//! assert_eq!(it.next(), Some(Ok((b"user/2".to_vec(), SeqMarked::new_tombstone(11)))));
+//! assert_eq!(
+//!     it.next(),
+//!     Some(Ok((b"user/3".to_vec(), SeqMarked::new_normal(9, b"bar".to_vec()))))
+//! );
+//! assert_eq!(it.next(), None);
+//! # Ok::<(), seq_marked::DecodeError>(())
+//! ```
+//!
+//! [`InternalKey`]: crate::InternalKey
+
+mod block_iter;
+mod builder;
+
+use alloc::vec::Vec;
+
+pub use block_iter::BlockIter;
+pub use builder::BlockBuilder;
+
+use crate::DecodeError;
+
+const NORMAL: u8 = 0;
+const TOMBSTONE: u8 = 1;
+
+/// Length of a restart position and of `num_restarts`.
+const U32_LEN: usize = 4;
+
+/// A decoded view of a block built by [`BlockBuilder`], over bytes of type `B`.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Block<B = Vec<u8>> {
+    data: B,
+
+    /// The position of the restart array, i.e., the length of the entries.
+    restarts_offset: usize,
+
+    num_restarts: usize,
+}
+
+impl<B> Block<B>
+where B: AsRef<[u8]>
+{
+    /// Creates a block from bytes built by [`BlockBuilder::finish()`].
+    ///
+    /// Only the restart array is validated; entries are validated when decoded.
+    pub fn new(data: B) -> Result<Self, DecodeError> {
+        let buf = data.as_ref();
+        if buf.len() < U32_LEN {
+            return Err(DecodeError::TooShort {
+                min: U32_LEN,
+                actual: buf.len(),
+            });
+        }
+
+        let num_restarts = u32::from_be_bytes(buf[buf.len() - U32_LEN..].try_into().unwrap());
+        let num_restarts = num_restarts as usize;
+
+        let trailer_len = (num_restarts + 1) * U32_LEN;
+        let Some(restarts_offset) = buf.len().checked_sub(trailer_len) else {
+            return Err(DecodeError::TooShort {
+                min: trailer_len,
+                actual: buf.len(),
+            });
+        };
+
+        let block = Self {
+            data,
+            restarts_offset,
+            num_restarts,
+        };
+
+        for i in 0..num_restarts {
+            let offset = block.restart(i);
+            if offset >= restarts_offset {
+                return Err(DecodeError::InvalidLength {
+                    expected: restarts_offset,
+                    actual: offset,
+                });
+            }
+        }
+
+        Ok(block)
+    }
+
+    /// Returns the number of restart points.
+    pub fn num_restarts(&self) -> usize {
+        self.num_restarts
+    }
+
+    /// Returns an iterator over the entries, starting from the first one.
+    pub fn iter(&self) -> BlockIter<&[u8]> {
+        BlockIter::new(Block {
+            data: self.data.as_ref(),
+            restarts_offset: self.restarts_offset,
+            num_restarts: self.num_restarts,
+        })
+    }
+
+    /// Consumes self and returns the underlying bytes.
+    pub fn into_inner(self) -> B {
+        self.data
+    }
+
+    /// Returns the bytes of the entries.
+    fn entries(&self) -> &[u8] {
+        &self.data.as_ref()[..self.restarts_offset]
+    }
+
+    /// Returns the position of the `i`-th restart point.
+    fn restart(&self, i: usize) -> usize {
+        let start = self.restarts_offset + i * U32_LEN;
+        let b = &self.data.as_ref()[start..start + U32_LEN];
+        u32::from_be_bytes(b.try_into().unwrap()) as usize
+    }
+}
+
+impl<B> IntoIterator for Block<B>
+where B: AsRef<[u8]>
+{
+    type Item = <BlockIter<B> as Iterator>::Item;
+    type IntoIter = BlockIter<B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BlockIter::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_invalid() {
+        assert_eq!(Block::new([0u8; 3]).unwrap_err(), DecodeError::TooShort {
+            min: 4,
+            actual: 3
+        });
+
+        // 2 restarts claimed, 1 present.
+        assert_eq!(
+            Block::new([0, 0, 0, 0, 0, 0, 0, 2]).unwrap_err(),
+            DecodeError::TooShort { min: 12, actual: 8 }
+        );
+
+        // Restart out of the entries.
+        assert_eq!(
+            Block::new([1, 0, 0, 0, 1, 0, 0, 0, 1]).unwrap_err(),
+            DecodeError::InvalidLength {
+                expected: 1,
+                actual: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let block = Block::new(BlockBuilder::new().finish()).unwrap();
+        assert_eq!(block.num_restarts(), 0);
+        assert_eq!(block.iter().count(), 0);
+        assert_eq!(block.into_inner(), vec![0, 0, 0, 0]);
+    }
+}
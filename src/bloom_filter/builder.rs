@@ -0,0 +1,132 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::BloomFilter;
+use crate::bloom_filter::hash;
+use crate::bloom_filter::probes;
+
+/// Builds a [`BloomFilter`] with a configurable number of bits per key.
+///
+/// More bits per key lowers the false positive rate at the cost of a larger filter:
+/// about 1% with 10 bits per key, 0.1% with 15.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct BloomFilterBuilder {
+    bits_per_key: usize,
+
+    /// The hashes of the added keys.
+    hashes: Vec<u64>,
+}
+
+impl BloomFilterBuilder {
+    /// The default number of bits per key.
+    pub const DEFAULT_BITS_PER_KEY: usize = 10;
+
+    /// The minimum number of bits of a filter, to keep the false positive rate of a small filter
+    /// low.
+    const MIN_BITS: usize = 64;
+
+    pub fn new(bits_per_key: usize) -> Self {
+        Self {
+            bits_per_key,
+            hashes: Vec::new(),
+        }
+    }
+
+    pub fn bits_per_key(&self) -> usize {
+        self.bits_per_key
+    }
+
+    /// Returns the number of added keys.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Adds a key; adding a key more than once has no more effect.
+    pub fn add(&mut self, key: &[u8]) {
+        self.hashes.push(hash(key));
+    }
+
+    /// Builds the filter of all added keys.
+    pub fn build(&self) -> BloomFilter {
+        let num_bits = (self.hashes.len() * self.bits_per_key).max(Self::MIN_BITS);
+        let num_bytes = num_bits.div_ceil(8);
+
+        // The optimal number of probes is `bits_per_key * ln(2)`.
+        let num_probes = (self.bits_per_key * 69 / 100).clamp(1, 30) as u8;
+
+        let mut bits = vec![0u8; num_bytes];
+        for h in &self.hashes {
+            for bit in probes(*h, num_probes, num_bytes as u64 * 8) {
+                bits[(bit / 8) as usize] |= 1 << (bit % 8);
+            }
+        }
+
+        BloomFilter { bits, num_probes }
+    }
+}
+
+impl Default for BloomFilterBuilder {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_BITS_PER_KEY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use super::*;
+
+    fn key(i: usize) -> Vec<u8> {
+        format!("key-{}", i).into_bytes()
+    }
+
+    #[test]
+    fn test_build() {
+        let mut b = BloomFilterBuilder::default();
+        assert_eq!(b.bits_per_key(), 10);
+        assert!(b.is_empty());
+
+        for i in 0..1000 {
+            b.add(&key(i));
+        }
+        assert_eq!(b.len(), 1000);
+
+        let f = b.build();
+        assert_eq!(f.num_bits(), 10_000);
+        assert_eq!(f.num_probes(), 6);
+
+        // No false negative.
+        for i in 0..1000 {
+            assert!(f.may_contain(&key(i)));
+        }
+
+        // About 1% false positive.
+        let fp = (1000..11_000).filter(|i| f.may_contain(&key(*i))).count();
+        assert!(fp < 200, "false positives: {}", fp);
+    }
+
+    #[test]
+    fn test_empty() {
+        let f = BloomFilterBuilder::new(10).build();
+        assert_eq!(f.num_bits(), 64);
+        assert!(!f.may_contain(b"a"));
+    }
+
+    #[test]
+    fn test_bits_per_key() {
+        let mut b = BloomFilterBuilder::new(1);
+        b.add(b"a");
+        let f = b.build();
+        assert_eq!(f.num_probes(), 1);
+        assert!(f.may_contain(b"a"));
+
+        let f = BloomFilterBuilder::new(100).build();
+        assert_eq!(f.num_probes(), 30);
+    }
+}
@@ -0,0 +1,140 @@
+//! Bloom filter over byte keys, to skip lookups of absent keys.
+
+mod builder;
+
+use alloc::vec::Vec;
+
+pub use builder::BloomFilterBuilder;
+
+use crate::DecodeError;
+
+/// A bloom filter over byte keys, built by [`BloomFilterBuilder`].
+///
+/// [`may_contain()`](Self::may_contain) never returns `false` for an added key, and returns
+/// `true` for an absent key with a small probability, about 1% with 10 bits per key.
+///
+/// A store must add every key it contains, including deleted ones: a tombstone must still be
+/// found to shadow the key in lower levels.
+///
+/// ```rust
+/// use seq_marked::BloomFilter;
+/// use seq_marked::BloomFilterBuilder;
+///
+/// let mut b = BloomFilterBuilder::new(10);
+/// b.add(b"a");
+/// b.add(b"b");
+/// let filter = b.build();
+///
+/// assert!(filter.may_contain(b"a"));
+/// assert!(filter.may_contain(b"b"));
+///
+/// let encoded = filter.encode();
+/// assert_eq!(BloomFilter::decode(&encoded).unwrap(), filter);
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Vec<u8>,
+    num_probes: u8,
+}
+
+impl BloomFilter {
+    /// Returns `false` if `key` is definitely not added, `true` if it may be added.
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        let num_bits = self.bits.len() as u64 * 8;
+        if num_bits == 0 {
+            return false;
+        }
+
+        probes(hash(key), self.num_probes, num_bits)
+            .all(|bit| self.bits[(bit / 8) as usize] & (1 << (bit % 8)) != 0)
+    }
+
+    /// Returns the number of bits of the filter.
+    pub fn num_bits(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Returns the number of bits checked for a key.
+    pub fn num_probes(&self) -> u8 {
+        self.num_probes
+    }
+
+    /// Encodes into bytes: the bit array followed by the number of probes.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.bits.len() + 1);
+        buf.extend_from_slice(&self.bits);
+        buf.push(self.num_probes);
+        buf
+    }
+
+    /// Decodes bytes built by [`encode()`](Self::encode).
+    pub fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
+        let Some((&num_probes, bits)) = buf.split_last() else {
+            return Err(DecodeError::TooShort { min: 1, actual: 0 });
+        };
+
+        Ok(Self {
+            bits: bits.to_vec(),
+            num_probes,
+        })
+    }
+}
+
+/// Returns the bit positions of a key, by double hashing.
+fn probes(hash: u64, num_probes: u8, num_bits: u64) -> impl Iterator<Item = u64> {
+    let h1 = hash & 0xffff_ffff;
+    let h2 = (hash >> 32) | 1;
+
+    (0..num_probes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+}
+
+/// A stable 64-bit hash of a key: FNV-1a with the MurmurHash3 finalizer.
+///
+/// It is part of the encoded filter thus it must never change.
+fn hash(key: &[u8]) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in key {
+        h ^= *b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^= h >> 33;
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_is_stable() {
+        assert_eq!(hash(b""), 0xefd0_1f60_ba99_2926);
+        assert_eq!(hash(b"a"), 0x82a2_a958_a9be_ce5b);
+        assert_ne!(hash(b"a"), hash(b"b"));
+    }
+
+    #[test]
+    fn test_decode() {
+        let f = BloomFilter {
+            bits: vec![1, 2],
+            num_probes: 3,
+        };
+        assert_eq!(f.encode(), vec![1, 2, 3]);
+        assert_eq!(BloomFilter::decode(&[1, 2, 3]), Ok(f));
+
+        assert_eq!(
+            BloomFilter::decode(&[]),
+            Err(DecodeError::TooShort { min: 1, actual: 0 })
+        );
+
+        let f = BloomFilter::decode(&[3]).unwrap();
+        assert_eq!(f.num_bits(), 0);
+        assert!(!f.may_contain(b"a"));
+    }
+}
@@ -0,0 +1,47 @@
+use crate::Change;
+use crate::SeqMarked;
+use crate::SeqV;
+
+/// Builds a [`Change`] from the `(prev, result)` values of a key stored as [`SeqMarked`].
+///
+/// A tombstone is converted to `None`.
+impl<M, T> From<(SeqMarked<(Option<M>, T)>, SeqMarked<(Option<M>, T)>)> for Change<M, T> {
+    fn from(value: (SeqMarked<(Option<M>, T)>, SeqMarked<(Option<M>, T)>)) -> Self {
+        let (prev, result) = value;
+
+        let prev: Option<SeqV<M, T>> = prev.into();
+        let result: Option<SeqV<M, T>> = result.into();
+
+        Change::new(prev, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_seq_marked() {
+        let prev = SeqMarked::new_normal(1, (Some("m"), 10u64));
+        let result = SeqMarked::new_normal(2, (None, 20u64));
+        let c: Change<&str, u64> = (prev, result).into();
+
+        assert_eq!(c.prev, Some(SeqV::new_with_meta(1, Some("m"), 10)));
+        assert_eq!(c.result, Some(SeqV::new(2, 20)));
+        assert!(c.is_update());
+    }
+
+    #[test]
+    fn test_from_seq_marked_tombstone() {
+        let prev = SeqMarked::new_normal(1, (None::<()>, 10u64));
+        let result = SeqMarked::new_tombstone(2);
+        let c: Change<(), u64> = (prev, result).into();
+
+        assert_eq!(c.prev, Some(SeqV::new(1, 10)));
+        assert_eq!(c.result, None);
+        assert!(c.is_delete());
+
+        let c: Change<(), u64> = (SeqMarked::new_not_found(), SeqMarked::new_tombstone(3)).into();
+        assert!(c.is_noop());
+    }
+}
@@ -0,0 +1,126 @@
+mod impl_from_seq_marked;
+
+use alloc::vec::Vec;
+
+use crate::SeqV;
+use crate::SeqValue;
+
+/// The state transition of a key caused by a write, for watch and notification streams.
+///
+/// `None` means the key is absent, before or after the write.
+///
+/// ```rust
+/// use seq_marked::Change;
+/// use seq_marked::SeqV;
+///
+/// let c = Change::<(), _>::new(None, Some(SeqV::new(1, "v")));
+/// assert!(c.is_insert());
+///
+/// let c = Change::<(), _>::new(Some(SeqV::new(1, "v")), None);
+/// assert!(c.is_delete());
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "seqv-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Change<M, T = Vec<u8>> {
+    /// The value before the write.
+    pub prev: Option<SeqV<M, T>>,
+
+    /// The value after the write.
+    pub result: Option<SeqV<M, T>>,
+}
+
+impl<M, T> Change<M, T> {
+    pub fn new(prev: Option<SeqV<M, T>>, result: Option<SeqV<M, T>>) -> Self {
+        Self { prev, result }
+    }
+
+    /// Returns `true` if an absent key is created.
+    pub fn is_insert(&self) -> bool {
+        self.prev.is_none() && self.result.is_some()
+    }
+
+    /// Returns `true` if the value of a present key is replaced.
+    pub fn is_update(&self) -> bool {
+        self.prev.is_some() && self.result.is_some() && !self.is_noop()
+    }
+
+    /// Returns `true` if a present key is removed.
+    pub fn is_delete(&self) -> bool {
+        self.prev.is_some() && self.result.is_none()
+    }
+
+    /// Returns `true` if the write changes nothing, i.e., the seq before and after are the same.
+    pub fn is_noop(&self) -> bool {
+        self.prev.seq() == self.result.seq()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sv(seq: u64, data: u64) -> Option<SeqV<(), u64>> {
+        Some(SeqV::new(seq, data))
+    }
+
+    #[test]
+    fn test_insert() {
+        let c = Change::new(None, sv(1, 10));
+        assert!(c.is_insert());
+        assert!(!c.is_update());
+        assert!(!c.is_delete());
+        assert!(!c.is_noop());
+    }
+
+    #[test]
+    fn test_update() {
+        let c = Change::new(sv(1, 10), sv(2, 20));
+        assert!(!c.is_insert());
+        assert!(c.is_update());
+        assert!(!c.is_delete());
+        assert!(!c.is_noop());
+    }
+
+    #[test]
+    fn test_delete() {
+        let c = Change::new(sv(1, 10), None);
+        assert!(!c.is_insert());
+        assert!(!c.is_update());
+        assert!(c.is_delete());
+        assert!(!c.is_noop());
+    }
+
+    #[test]
+    fn test_noop() {
+        let c = Change::<(), u64>::new(None, None);
+        assert!(!c.is_insert());
+        assert!(!c.is_update());
+        assert!(!c.is_delete());
+        assert!(c.is_noop());
+
+        let c = Change::new(sv(1, 10), sv(1, 10));
+        assert!(!c.is_insert());
+        assert!(!c.is_update());
+        assert!(!c.is_delete());
+        assert!(c.is_noop());
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "seqv-serde")]
+mod tests_serde {
+    use super::*;
+
+    #[test]
+    fn test_serde() {
+        let c = Change::new(
+            Some(SeqV::new_with_meta(1, Some("m".to_string()), vec![1, 2])),
+            None,
+        );
+        let json = serde_json::to_string(&c).unwrap();
+        let decoded: Change<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(c, decoded);
+    }
+}
@@ -0,0 +1,63 @@
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+
+use crate::Clock;
+
+/// A [`Clock`] whose time only changes when it is set or advanced, for deterministic tests.
+///
+/// It can be shared between threads, e.g., in an `Arc`.
+///
+/// ```rust
+/// use seq_marked::Clock;
+/// use seq_marked::ManualClock;
+///
+/// let clock = ManualClock::new(1000);
+/// clock.advance(500);
+/// assert_eq!(clock.now_ms(), 1500);
+/// ```
+#[derive(Debug)]
+#[derive(Default)]
+pub struct ManualClock {
+    now_ms: AtomicU64,
+}
+
+impl ManualClock {
+    pub fn new(now_ms: u64) -> Self {
+        Self {
+            now_ms: AtomicU64::new(now_ms),
+        }
+    }
+
+    /// Sets the current time to `now_ms`.
+    pub fn set(&self, now_ms: u64) {
+        self.now_ms.store(now_ms, Ordering::Release);
+    }
+
+    /// Moves the current time forward by `ms` and returns the new time.
+    pub fn advance(&self, ms: u64) -> u64 {
+        self.now_ms.fetch_add(ms, Ordering::AcqRel) + ms
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let c = ManualClock::default();
+        assert_eq!(c.now_ms(), 0);
+
+        c.set(10);
+        assert_eq!(c.now_ms(), 10);
+
+        assert_eq!(c.advance(5), 15);
+        assert_eq!(c.now_ms(), 15);
+    }
+}
@@ -0,0 +1,44 @@
+//! Source of the current time for expiration checks.
+
+mod manual_clock;
+#[cfg(feature = "std")]
+mod system_clock;
+
+pub use manual_clock::ManualClock;
+#[cfg(feature = "std")]
+pub use system_clock::SystemClock;
+
+/// A source of the current time in milliseconds since the Unix epoch (1970-01-01 00:00:00 UTC).
+///
+/// Use `SystemClock` in production and [`ManualClock`] in tests, so that TTL logic can be
+/// tested without sleeping.
+pub trait Clock {
+    /// Returns the current time in milliseconds since the Unix epoch.
+    fn now_ms(&self) -> u64;
+}
+
+impl<C> Clock for &C
+where C: Clock + ?Sized
+{
+    fn now_ms(&self) -> u64 {
+        (**self).now_ms()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now_of(clock: impl Clock) -> u64 {
+        clock.now_ms()
+    }
+
+    #[test]
+    fn test_clock_ref() {
+        let c = ManualClock::new(5);
+        assert_eq!(now_of(&c), 5);
+
+        let c: &dyn Clock = &c;
+        assert_eq!(now_of(c), 5);
+    }
+}
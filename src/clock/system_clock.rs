@@ -0,0 +1,35 @@
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use crate::Clock;
+
+/// A [`Clock`] reading the wall-clock time of the system.
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    /// Returns the system time, or 0 if the system time is before the Unix epoch.
+    fn now_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock() {
+        let a = SystemClock.now_ms();
+        let b = SystemClock.now_ms();
+
+        // 2020-01-01 00:00:00 UTC
+        assert!(a > 1_577_836_800_000);
+        assert!(b >= a);
+    }
+}
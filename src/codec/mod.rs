@@ -0,0 +1,156 @@
+//! Helpers to encode and decode big-endian, `u32` length-prefixed binary formats.
+
+use alloc::vec::Vec;
+
+use crate::DecodeError;
+
+pub(crate) fn put_len(buf: &mut Vec<u8>, len: usize) {
+    let len = u32::try_from(len).expect("length exceeds u32::MAX");
+    buf.extend_from_slice(&len.to_be_bytes());
+}
+
+pub(crate) fn put_bytes(buf: &mut Vec<u8>, b: &[u8]) {
+    put_len(buf, b.len());
+    buf.extend_from_slice(b);
+}
+
+/// The maximum length of a `u64` varint.
+pub(crate) const MAX_VARINT_LEN: usize = 10;
+
+/// Appends `v` as an unsigned LEB128 varint.
+pub(crate) fn put_varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+/// Maps a signed integer to an unsigned one, so that small magnitudes encode to short varints.
+pub(crate) fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+pub(crate) fn unzigzag(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+/// Consumes bytes from the front of a buffer.
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    /// Returns the bytes not consumed yet.
+    pub(crate) fn remaining(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if self.buf.len() < n {
+            return Err(DecodeError::TooShort {
+                min: n,
+                actual: self.buf.len(),
+            });
+        }
+
+        let (head, rest) = self.buf.split_at(n);
+        self.buf = rest;
+        Ok(head)
+    }
+
+    pub(crate) fn take_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut a = [0u8; N];
+        a.copy_from_slice(self.take(N)?);
+        Ok(a)
+    }
+
+    /// Consumes an unsigned LEB128 varint of at most [`MAX_VARINT_LEN`] bytes.
+    pub(crate) fn take_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut v = 0u64;
+        for i in 0..MAX_VARINT_LEN {
+            let [b] = self.take_array()?;
+            v |= ((b & 0x7f) as u64) << (7 * i);
+            if b < 0x80 {
+                return Ok(v);
+            }
+        }
+
+        Err(DecodeError::InvalidLength {
+            expected: MAX_VARINT_LEN,
+            actual: MAX_VARINT_LEN + 1,
+        })
+    }
+
+    pub(crate) fn take_bytes(&mut self) -> Result<Vec<u8>, DecodeError> {
+        let len = u32::from_be_bytes(self.take_array()?) as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reader() {
+        let mut buf = vec![];
+        put_bytes(&mut buf, b"ab");
+        buf.push(3);
+
+        let mut r = Reader::new(&buf);
+        assert_eq!(r.take_bytes(), Ok(b"ab".to_vec()));
+        assert_eq!(r.remaining(), &[3]);
+        assert_eq!(
+            r.take_array::<2>(),
+            Err(DecodeError::TooShort { min: 2, actual: 1 })
+        );
+        assert_eq!(r.take(1), Ok(&[3][..]));
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_varint() {
+        for v in [0, 1, 0x7f, 0x80, 0x3fff, 0x4000, u32::MAX as u64, u64::MAX] {
+            let mut buf = vec![];
+            put_varint(&mut buf, v);
+
+            let mut r = Reader::new(&buf);
+            assert_eq!(r.take_varint(), Ok(v));
+            assert!(r.is_empty());
+
+            assert!(Reader::new(&buf[..buf.len() - 1]).take_varint().is_err());
+        }
+
+        let mut buf = vec![];
+        put_varint(&mut buf, 300);
+        assert_eq!(buf, vec![0xac, 0x02]);
+
+        // Too long
+        let buf = [0x80; 11];
+        assert!(Reader::new(&buf).take_varint().is_err());
+    }
+
+    #[test]
+    fn test_zigzag() {
+        for (v, z) in [
+            (0, 0),
+            (-1, 1),
+            (1, 2),
+            (-2, 3),
+            (i64::MAX, u64::MAX - 1),
+            (i64::MIN, u64::MAX),
+        ] {
+            assert_eq!(zigzag(v), z);
+            assert_eq!(unzigzag(z), v);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::CompactionFilter;
+use crate::InternalSeq;
+use crate::SeqMarked;
+
+/// Iterator over the versions of a key that survive a [`CompactionFilter`].
+///
+/// Created by [`CompactionFilter::filter`].
+pub struct Filtered<'f, I> {
+    filter: &'f CompactionFilter,
+    versions: I,
+
+    /// The seq of the last consumed version, which is newer than the next one.
+    newer_seq: Option<InternalSeq>,
+}
+
+impl<'f, I> Filtered<'f, I> {
+    pub(crate) fn new(filter: &'f CompactionFilter, versions: I) -> Self {
+        Self {
+            filter,
+            versions,
+            newer_seq: None,
+        }
+    }
+}
+
+impl<D, I> Iterator for Filtered<'_, I>
+where I: Iterator<Item = SeqMarked<D>>
+{
+    type Item = SeqMarked<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for version in self.versions.by_ref() {
+            let seq = version.internal_seq();
+            let newer_seq = self.newer_seq.replace(seq);
+
+            if !self.filter.is_observed(seq, newer_seq) {
+                continue;
+            }
+
+            if version.is_tombstone() && self.filter.is_obsolete_tombstone(seq) {
+                continue;
+            }
+
+            return Some(version);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    fn filter(
+        snapshots: impl IntoIterator<Item = u64>,
+        bottommost: bool,
+        versions: Vec<SeqMarked<&'static str>>,
+    ) -> Vec<SeqMarked<&'static str>> {
+        let f = CompactionFilter::new(snapshots.into_iter().map(InternalSeq::new), bottommost);
+        f.filter(versions.into_iter()).collect()
+    }
+
+    #[test]
+    fn test_filter_without_snapshot_keeps_newest() {
+        let versions = vec![norm(5, "c"), norm(3, "b"), norm(1, "a")];
+        assert_eq!(filter([], false, versions.clone()), vec![norm(5, "c")]);
+        assert_eq!(filter([], true, versions), vec![norm(5, "c")]);
+
+        assert_eq!(filter([], false, vec![]), vec![]);
+    }
+
+    #[test]
+    fn test_filter_keeps_versions_observed_by_snapshots() {
+        let versions = vec![norm(9, "d"), norm(7, "c"), norm(4, "b"), norm(2, "a")];
+
+        assert_eq!(filter([4], false, versions.clone()), vec![
+            norm(9, "d"),
+            norm(4, "b")
+        ]);
+        assert_eq!(filter([5, 6], false, versions.clone()), vec![
+            norm(9, "d"),
+            norm(4, "b")
+        ]);
+        assert_eq!(filter([3, 8], false, versions.clone()), vec![
+            norm(9, "d"),
+            norm(7, "c"),
+            norm(2, "a")
+        ]);
+        assert_eq!(filter([1], false, versions.clone()), vec![norm(9, "d")]);
+        assert_eq!(filter([100], false, versions), vec![norm(9, "d")]);
+    }
+
+    #[test]
+    fn test_filter_normal_shadowed_by_tombstone_at_same_seq() {
+        let versions = vec![ts(5), norm(5, "a")];
+        assert_eq!(filter([5], false, versions), vec![ts(5)]);
+    }
+
+    #[test]
+    fn test_filter_tombstone_not_bottommost() {
+        let versions = vec![ts(5), norm(3, "a")];
+        assert_eq!(filter([], false, versions.clone()), vec![ts(5)]);
+        assert_eq!(filter([4], false, versions), vec![ts(5), norm(3, "a")]);
+    }
+
+    #[test]
+    fn test_filter_tombstone_bottommost() {
+        let versions = vec![ts(5), norm(3, "a")];
+
+        // No snapshot: the tombstone shadows nothing that has to be kept.
+        assert_eq!(filter([], true, versions.clone()), vec![]);
+
+        // All snapshots observe the tombstone.
+        assert_eq!(filter([5, 6], true, versions.clone()), vec![]);
+
+        // Snapshot 4 observes the older normal, which must stay shadowed for newer readers.
+        assert_eq!(filter([4], true, versions.clone()), vec![
+            ts(5),
+            norm(3, "a")
+        ]);
+
+        // A newer normal above the tombstone.
+        let versions = vec![norm(8, "b"), ts(5), norm(3, "a")];
+        assert_eq!(filter([6], true, versions.clone()), vec![norm(8, "b")]);
+        assert_eq!(filter([4, 6], true, versions), vec![
+            norm(8, "b"),
+            ts(5),
+            norm(3, "a")
+        ]);
+    }
+}
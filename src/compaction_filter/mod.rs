@@ -0,0 +1,159 @@
+//! Decides which versions of a key survive a compaction.
+
+mod filtered;
+
+use alloc::vec::Vec;
+
+pub use filtered::Filtered;
+
+use crate::InternalSeq;
+#[cfg(doc)]
+use crate::SeqMarked;
+
+/// Snapshot-aware policy that drops shadowed versions and obsolete tombstones.
+///
+/// A snapshot at seq `s` observes the newest version whose seq is `<= s`.
+/// A version survives if it is the newest version of the key, or if it is observed by at least one
+/// snapshot. Every other version is shadowed and is dropped.
+///
+/// When compacting into the bottommost level, a surviving tombstone is dropped too if its seq is
+/// not greater than the oldest snapshot: every snapshot observes either this tombstone or a newer
+/// version, and there is no lower level in which it has to shadow anything.
+///
+/// ```rust
+/// use seq_marked::CompactionFilter;
+/// use seq_marked::InternalSeq;
+/// use seq_marked::SeqMarked;
+///
+/// let versions = vec![
+///     SeqMarked::new_normal(9, "c"),
+///     SeqMarked::<&str>::new_tombstone(6),
+///     SeqMarked::new_normal(3, "a"),
+/// ];
+///
+/// let filter = CompactionFilter::new([InternalSeq::new(4)], false);
+/// let got = filter.filter(versions.into_iter()).collect::<Vec<_>>();
+/// assert_eq!(got, vec![SeqMarked::new_normal(9, "c"), SeqMarked::new_normal(3, "a")]);
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct CompactionFilter {
+    /// Live snapshots, sorted in ascending order without duplicates.
+    snapshots: Vec<InternalSeq>,
+
+    /// Whether the output is written to the bottommost level.
+    bottommost: bool,
+}
+
+impl CompactionFilter {
+    /// Creates a filter with the live snapshots and whether the output goes to the bottommost
+    /// level.
+    pub fn new(snapshots: impl IntoIterator<Item = InternalSeq>, bottommost: bool) -> Self {
+        let mut snapshots = snapshots.into_iter().collect::<Vec<_>>();
+        snapshots.sort();
+        snapshots.dedup();
+
+        Self {
+            snapshots,
+            bottommost,
+        }
+    }
+
+    /// Returns the live snapshots in ascending order.
+    pub fn snapshots(&self) -> &[InternalSeq] {
+        &self.snapshots
+    }
+
+    /// Returns the oldest live snapshot, or `None` if there is no snapshot.
+    pub fn oldest_snapshot(&self) -> Option<InternalSeq> {
+        self.snapshots.first().copied()
+    }
+
+    /// Returns `true` if the output is written to the bottommost level.
+    pub fn is_bottommost(&self) -> bool {
+        self.bottommost
+    }
+
+    /// Filters the versions of a single key, which must be sorted by
+    /// [`SeqMarked::order_key`] in descending order, i.e., newest first.
+    pub fn filter<I>(&self, versions: I) -> Filtered<'_, I> {
+        Filtered::new(self, versions)
+    }
+
+    /// Returns `true` if a snapshot in `[seq, newer_seq)` exists, i.e., a snapshot observes the
+    /// version at `seq` whose next newer version is at `newer_seq`.
+    ///
+    /// `newer_seq` is `None` for the newest version, which is always observed.
+    fn is_observed(&self, seq: InternalSeq, newer_seq: Option<InternalSeq>) -> bool {
+        let Some(newer_seq) = newer_seq else {
+            return true;
+        };
+
+        let i = self.snapshots.partition_point(|s| *s < seq);
+        self.snapshots.get(i).is_some_and(|s| *s < newer_seq)
+    }
+
+    /// Returns `true` if an observed tombstone at `seq` does not need to be kept.
+    fn is_obsolete_tombstone(&self, seq: InternalSeq) -> bool {
+        if !self.bottommost {
+            return false;
+        }
+
+        match self.oldest_snapshot() {
+            None => true,
+            Some(oldest) => seq <= oldest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iseqs(seqs: impl IntoIterator<Item = u64>) -> Vec<InternalSeq> {
+        seqs.into_iter().map(InternalSeq::new).collect()
+    }
+
+    #[test]
+    fn test_new_sorts_snapshots() {
+        let f = CompactionFilter::new(iseqs([5, 1, 3, 1]), true);
+        assert_eq!(f.snapshots(), &iseqs([1, 3, 5]));
+        assert_eq!(f.oldest_snapshot(), Some(InternalSeq::new(1)));
+        assert!(f.is_bottommost());
+
+        let f = CompactionFilter::new([], false);
+        assert_eq!(f.oldest_snapshot(), None);
+        assert!(!f.is_bottommost());
+    }
+
+    #[test]
+    fn test_is_observed() {
+        let f = CompactionFilter::new(iseqs([3, 7]), false);
+        let is_observed = |seq, newer: Option<u64>| {
+            f.is_observed(InternalSeq::new(seq), newer.map(InternalSeq::new))
+        };
+
+        assert!(is_observed(1, None));
+        assert!(is_observed(1, Some(4)));
+        assert!(is_observed(3, Some(4)));
+        assert!(!is_observed(4, Some(7)));
+        assert!(is_observed(4, Some(8)));
+        assert!(!is_observed(1, Some(3)));
+        assert!(!is_observed(8, Some(9)));
+    }
+
+    #[test]
+    fn test_is_obsolete_tombstone() {
+        let f = CompactionFilter::new(iseqs([3, 7]), false);
+        assert!(!f.is_obsolete_tombstone(InternalSeq::new(1)));
+
+        let f = CompactionFilter::new(iseqs([3, 7]), true);
+        assert!(f.is_obsolete_tombstone(InternalSeq::new(1)));
+        assert!(f.is_obsolete_tombstone(InternalSeq::new(3)));
+        assert!(!f.is_obsolete_tombstone(InternalSeq::new(4)));
+
+        let f = CompactionFilter::new([], true);
+        assert!(f.is_obsolete_tombstone(InternalSeq::new(100)));
+    }
+}
@@ -0,0 +1,144 @@
+use alloc::string::FromUtf8Error;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+/// Error converting a value, e.g., the bytes of a value to a `String`.
+///
+/// It keeps the seq of the value, if any, and the original bytes for recovery.
+///
+/// ```rust
+/// use seq_marked::ConversionError;
+/// use seq_marked::SeqMarked;
+///
+/// let v = SeqMarked::new_normal(5, (None::<()>, vec![0xFF]));
+/// let err = SeqMarked::<(Option<()>, String)>::try_from(v).unwrap_err();
+///
+/// assert_eq!(err.seq(), Some(5));
+/// assert_eq!(err.into_bytes(), vec![0xFF]);
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConversionError {
+    /// The bytes are not valid UTF-8.
+    Utf8 {
+        /// The seq of the value, `None` if the value has no seq.
+        seq: Option<u64>,
+
+        /// The source error, which holds the original bytes.
+        source: FromUtf8Error,
+    },
+}
+
+impl ConversionError {
+    /// Creates an error for bytes that are not valid UTF-8, without a seq.
+    pub fn new_utf8(source: FromUtf8Error) -> Self {
+        Self::Utf8 { seq: None, source }
+    }
+
+    /// Sets the seq of the value that fails to convert.
+    #[must_use]
+    pub fn with_seq(self, seq: u64) -> Self {
+        match self {
+            Self::Utf8 { source, .. } => Self::Utf8 {
+                seq: Some(seq),
+                source,
+            },
+        }
+    }
+
+    /// Returns the seq of the value that fails to convert, if any.
+    pub fn seq(&self) -> Option<u64> {
+        match self {
+            Self::Utf8 { seq, .. } => *seq,
+        }
+    }
+
+    /// Returns the original bytes.
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Utf8 { source, .. } => source.as_bytes(),
+        }
+    }
+
+    /// Consumes the error and returns the original bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            Self::Utf8 { source, .. } => source.into_bytes(),
+        }
+    }
+}
+
+impl From<FromUtf8Error> for ConversionError {
+    fn from(source: FromUtf8Error) -> Self {
+        Self::new_utf8(source)
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utf8 { seq, source } => {
+                write!(f, "fail to convert Vec<u8> to String")?;
+                if let Some(seq) = seq {
+                    write!(f, " at seq {}", seq)?;
+                }
+                write!(f, ": {}", source)
+            }
+        }
+    }
+}
+
+impl Error for ConversionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Utf8 { source, .. } => Some(source),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ConversionError> for std::io::Error {
+    fn from(e: ConversionError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf8_error() -> FromUtf8Error {
+        String::from_utf8(vec![b'a', 0xFF]).unwrap_err()
+    }
+
+    #[test]
+    fn test_utf8() {
+        let err = ConversionError::from(utf8_error());
+
+        assert_eq!(err.seq(), None);
+        assert_eq!(err.bytes(), &[b'a', 0xFF]);
+        assert_eq!(
+            err.to_string(),
+            "fail to convert Vec<u8> to String: invalid utf-8 sequence of 1 bytes from index 1"
+        );
+        assert!(err.source().is_some());
+
+        let err = err.with_seq(3);
+        assert_eq!(err.seq(), Some(3));
+        assert_eq!(
+            err.to_string(),
+            "fail to convert Vec<u8> to String at seq 3: invalid utf-8 sequence of 1 bytes from index 1"
+        );
+        assert_eq!(err.into_bytes(), vec![b'a', 0xFF]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_into_io_error() {
+        let io_err = std::io::Error::from(ConversionError::new_utf8(utf8_error()));
+        assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
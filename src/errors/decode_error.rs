@@ -0,0 +1,90 @@
+use core::error::Error;
+use core::fmt;
+
+/// Error decoding the binary encodings of this crate, such as the sortable bytes of an order key.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input does not have the expected length.
+    InvalidLength { expected: usize, actual: usize },
+
+    /// The input is shorter than the minimum length.
+    TooShort { min: usize, actual: usize },
+
+    /// The mark byte is neither normal nor tombstone.
+    InvalidMark(u8),
+
+    /// Assigning seqs to `count` operations from `first_seq` overflows `u64`.
+    SeqOverflow { first_seq: u64, count: u64 },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength { expected, actual } => {
+                write!(f, "invalid length: expected {}, got {}", expected, actual)
+            }
+            DecodeError::TooShort { min, actual } => {
+                write!(f, "too short: expected at least {}, got {}", min, actual)
+            }
+            DecodeError::InvalidMark(b) => write!(f, "invalid mark byte: {}", b),
+            DecodeError::SeqOverflow { first_seq, count } => {
+                write!(
+                    f,
+                    "seq overflow: {} operations from seq {}",
+                    count, first_seq
+                )
+            }
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+#[cfg(feature = "std")]
+impl From<DecodeError> for std::io::Error {
+    fn from(e: DecodeError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            DecodeError::InvalidLength {
+                expected: 9,
+                actual: 8
+            }
+            .to_string(),
+            "invalid length: expected 9, got 8"
+        );
+        assert_eq!(
+            DecodeError::TooShort { min: 9, actual: 3 }.to_string(),
+            "too short: expected at least 9, got 3"
+        );
+        assert_eq!(
+            DecodeError::InvalidMark(2).to_string(),
+            "invalid mark byte: 2"
+        );
+        assert_eq!(
+            DecodeError::SeqOverflow {
+                first_seq: 5,
+                count: 3
+            }
+            .to_string(),
+            "seq overflow: 3 operations from seq 5"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_into_io_error() {
+        let io_err = std::io::Error::from(DecodeError::InvalidMark(2));
+        assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,7 @@
+//! Errors of conversions and decoding, usable without `std`.
+
+mod conversion_error;
+mod decode_error;
+
+pub use conversion_error::ConversionError;
+pub use decode_error::DecodeError;
@@ -6,6 +6,8 @@
 
 mod expirable_impl;
 
+use crate::Clock;
+
 /// A trait for evaluating and returning the absolute expiration time.
 pub trait Expirable {
     /// Returns the optional expiration time in milliseconds since the Unix epoch (January 1, 1970).
@@ -18,11 +20,20 @@ pub trait Expirable {
     fn expires_at_ms(&self) -> u64 {
         self.expires_at_ms_opt().unwrap_or(u64::MAX)
     }
+
+    /// Returns `true` if it is expired at the current time of `clock`.
+    ///
+    /// It is expired if the expiration time is less than the current time.
+    fn is_expired_with(&self, clock: &impl Clock) -> bool
+    where Self: Sized {
+        self.expires_at_ms() < clock.now_ms()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ManualClock;
 
     #[derive(Clone, Copy)]
     struct ExpirableImpl {
@@ -83,4 +94,25 @@ mod tests {
             assert_eq!(e2_opt.expires_at_ms(), 1);
         }
     }
+
+    #[test]
+    fn test_is_expired_with() {
+        let clock = ManualClock::new(1);
+
+        let e = ExpirableImpl {
+            expires_at_ms: Some(1),
+        };
+        assert!(!e.is_expired_with(&clock));
+        assert!(!Some(e).is_expired_with(&clock));
+
+        clock.advance(1);
+        assert!(e.is_expired_with(&clock));
+        assert!(Some(e).is_expired_with(&clock));
+
+        let e = ExpirableImpl {
+            expires_at_ms: None,
+        };
+        clock.set(u64::MAX);
+        assert!(!e.is_expired_with(&clock));
+    }
 }
@@ -0,0 +1,237 @@
+//! Secondary index of keys ordered by expiration time.
+
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::Clock;
+use crate::Expirable;
+use crate::SeqValue;
+
+/// Secondary index of `(expires_at_ms, key)`, to find expired keys without a full scan.
+///
+/// It must be updated on every write of the primary store: [`put()`](Self::put) when a value is
+/// written and [`delete()`](Self::delete) when a key is removed. Only the latest expiration time
+/// of a key is indexed, and a value without expiration time is not indexed.
+///
+/// ```rust
+/// use seq_marked::ExpireIndex;
+/// use seq_marked::SeqV;
+///
+/// # #[derive(Clone)]
+/// # struct Meta(Option<u64>);
+/// # impl seq_marked::Expirable for Meta {
+/// #     fn expires_at_ms_opt(&self) -> Option<u64> { self.0 }
+/// # }
+/// let mut index = ExpireIndex::new();
+///
+/// index.put("a", &SeqV::new_with_meta(1, Some(Meta(Some(10))), ()));
+/// index.put("b", &SeqV::new_with_meta(2, Some(Meta(Some(20))), ()));
+///
+/// // "a" is updated with a later expiration time.
+/// index.put("a", &SeqV::new_with_meta(3, Some(Meta(Some(30))), ()));
+///
+/// assert_eq!(index.drain_expired(25), vec!["b"]);
+/// assert_eq!(index.drain_expired(25), Vec::<&str>::new());
+/// assert_eq!(index.drain_expired(31), vec!["a"]);
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct ExpireIndex<K> {
+    /// Keys ordered by expiration time.
+    by_time: BTreeSet<(u64, K)>,
+
+    /// The indexed expiration time of every key.
+    by_key: BTreeMap<K, u64>,
+}
+
+impl<K> Default for ExpireIndex<K> {
+    fn default() -> Self {
+        Self {
+            by_time: BTreeSet::new(),
+            by_key: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K> ExpireIndex<K>
+where K: Ord + Clone
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of indexed keys.
+    pub fn len(&self) -> usize {
+        self.by_key.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_key.is_empty()
+    }
+
+    /// Returns the indexed expiration time of `key`, or `None` if it is not indexed.
+    pub fn get(&self, key: &K) -> Option<u64> {
+        self.by_key.get(key).copied()
+    }
+
+    /// Returns the earliest indexed expiration time.
+    pub fn next_expire_at(&self) -> Option<u64> {
+        self.by_time.first().map(|(t, _)| *t)
+    }
+
+    /// Updates the index for a new version `value` of `key`.
+    ///
+    /// The expiration time of the previous version, if any, is replaced.
+    pub fn put<M, T>(&mut self, key: K, value: &impl SeqValue<M, T>)
+    where M: Expirable {
+        self.remove(&key);
+
+        if let Some(expires_at) = value.expires_at_ms_opt() {
+            self.by_time.insert((expires_at, key.clone()));
+            self.by_key.insert(key, expires_at);
+        }
+    }
+
+    /// Removes `key` from the index, when it is deleted from the primary store.
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+
+    /// Removes and returns the keys expired at `now_ms`, ordered by expiration time.
+    ///
+    /// A key is expired if its expiration time is less than `now_ms`, consistent with
+    /// [`SeqValue::is_expired()`]. The caller is responsible for writing tombstones for them.
+    pub fn drain_expired(&mut self, now_ms: u64) -> Vec<K> {
+        let mut expired = vec![];
+
+        while let Some((expires_at, _)) = self.by_time.first() {
+            if *expires_at >= now_ms {
+                break;
+            }
+
+            let (_, key) = self.by_time.pop_first().unwrap();
+            self.by_key.remove(&key);
+            expired.push(key);
+        }
+
+        expired
+    }
+
+    /// Removes and returns the keys expired at the current time of `clock`.
+    pub fn drain_expired_with(&mut self, clock: &impl Clock) -> Vec<K> {
+        self.drain_expired(clock.now_ms())
+    }
+
+    fn remove(&mut self, key: &K) {
+        if let Some(expires_at) = self.by_key.remove(key) {
+            self.by_time.remove(&(expires_at, key.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManualClock;
+    use crate::SeqV;
+
+    #[derive(Clone)]
+    struct Meta(Option<u64>);
+
+    impl Expirable for Meta {
+        fn expires_at_ms_opt(&self) -> Option<u64> {
+            self.0
+        }
+    }
+
+    fn sv(seq: u64, expires_at: Option<u64>) -> SeqV<Meta, ()> {
+        SeqV::new_with_meta(seq, Some(Meta(expires_at)), ())
+    }
+
+    #[test]
+    fn test_put_and_drain() {
+        let mut index = ExpireIndex::new();
+        assert!(index.is_empty());
+        assert_eq!(index.next_expire_at(), None);
+
+        index.put(1, &sv(1, Some(30)));
+        index.put(2, &sv(2, Some(10)));
+        index.put(3, &sv(3, Some(20)));
+        index.put(4, &sv(4, None));
+        index.put(5, &SeqV::<Meta, ()>::new(5, ()));
+
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get(&1), Some(30));
+        assert_eq!(index.get(&4), None);
+        assert_eq!(index.next_expire_at(), Some(10));
+
+        assert_eq!(index.drain_expired(10), Vec::<u64>::new());
+        assert_eq!(index.drain_expired(21), vec![2, 3]);
+        assert_eq!(index.next_expire_at(), Some(30));
+        assert_eq!(index.drain_expired(u64::MAX), vec![1]);
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_expiry_changes_across_versions() {
+        let mut index = ExpireIndex::new();
+
+        index.put(1, &sv(1, Some(10)));
+        index.put(1, &sv(2, Some(30)));
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.drain_expired(20), Vec::<u64>::new());
+
+        index.put(1, &sv(3, Some(5)));
+        assert_eq!(index.drain_expired(20), vec![1]);
+
+        // The expiration time is removed by a new version.
+        index.put(2, &sv(4, Some(10)));
+        index.put(2, &sv(5, None));
+        assert!(index.is_empty());
+        assert_eq!(index.drain_expired(u64::MAX), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut index = ExpireIndex::new();
+
+        index.put(1, &sv(1, Some(10)));
+        index.put(2, &sv(2, Some(10)));
+        index.delete(&1);
+        index.delete(&3);
+
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.drain_expired(11), vec![2]);
+    }
+
+    #[test]
+    fn test_consistent_with_is_expired() {
+        let mut index = ExpireIndex::new();
+        let v = sv(1, Some(10));
+        index.put(1, &v);
+
+        for now in [9, 10, 11] {
+            let mut idx = index.clone();
+            assert_eq!(!idx.drain_expired(now).is_empty(), v.is_expired(now));
+        }
+    }
+
+    #[test]
+    fn test_consistent_with_is_expired_with_clock() {
+        let mut index = ExpireIndex::new();
+        let v = sv(1, Some(10));
+        index.put(1, &v);
+
+        let clock = ManualClock::new(9);
+        for _ in 0..3 {
+            let mut idx = index.clone();
+            assert_eq!(
+                !idx.drain_expired_with(&clock).is_empty(),
+                v.is_expired_with(&clock)
+            );
+            clock.advance(1);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::DecodeError;
+use crate::InternalSeq;
+use crate::Marked;
+use crate::SeqMarked;
+
+/// Length of the seq and mark trailer appended to the user key in the encoded form.
+const TRAILER_LEN: usize = SeqMarked::<()>::SORTABLE_BYTES_LEN;
+
+/// Internal key of an LSM entry: user key, [`InternalSeq`] and whether it is a tombstone.
+///
+/// Ordered by user key ascending, then by order key descending, so that the newest version of a
+/// user key comes first, and a tombstone comes before a normal value with the same seq.
+///
+/// ```rust
+/// use seq_marked::InternalKey;
+/// use seq_marked::InternalSeq;
+///
+/// let a1 = InternalKey::new_normal("a", InternalSeq::new(1));
+/// let a2 = InternalKey::new_normal("a", InternalSeq::new(2));
+/// let b1 = InternalKey::new_tombstone("b", InternalSeq::new(1));
+///
+/// assert!(a2 < a1);
+/// assert!(a1 < b1);
+/// ```
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+pub struct InternalKey<K = Vec<u8>> {
+    user_key: K,
+    seq: InternalSeq,
+    marked: Marked<()>,
+}
+
+impl<K> InternalKey<K> {
+    /// Creates an internal key from the user key, seq and mark.
+    pub fn new(user_key: K, seq: InternalSeq, marked: Marked<()>) -> Self {
+        Self {
+            user_key,
+            seq,
+            marked,
+        }
+    }
+
+    /// Creates an internal key of a normal value.
+    pub fn new_normal(user_key: K, seq: InternalSeq) -> Self {
+        Self::new(user_key, seq, Marked::Normal(()))
+    }
+
+    /// Creates an internal key of a tombstone.
+    pub fn new_tombstone(user_key: K, seq: InternalSeq) -> Self {
+        Self::new(user_key, seq, Marked::TombStone)
+    }
+
+    /// Creates the internal key of a `value` stored under `user_key`.
+    pub fn from_seq_marked<D>(user_key: K, value: &SeqMarked<D>) -> Self {
+        let (seq, marked) = value.order_key().into_parts();
+        Self::new(user_key, InternalSeq::new(seq), marked)
+    }
+
+    pub fn user_key(&self) -> &K {
+        &self.user_key
+    }
+
+    pub fn seq(&self) -> InternalSeq {
+        self.seq
+    }
+
+    /// Returns `true` if this is the key of a tombstone.
+    pub fn is_tombstone(&self) -> bool {
+        self.marked == Marked::TombStone
+    }
+
+    /// Returns the order key (sequence + tombstone state only).
+    pub fn order_key(&self) -> SeqMarked<()> {
+        SeqMarked::new(*self.seq, self.marked)
+    }
+
+    pub fn into_parts(self) -> (K, InternalSeq, Marked<()>) {
+        (self.user_key, self.seq, self.marked)
+    }
+}
+
+impl<K> InternalKey<K>
+where K: AsRef<[u8]>
+{
+    /// Returns the length of the encoded form.
+    pub fn encoded_len(&self) -> usize {
+        self.user_key.as_ref().len() + TRAILER_LEN
+    }
+
+    /// Encodes into the user key followed by the descending sortable bytes of the order key.
+    ///
+    /// The encoded keys are compared with [`compare_encoded()`](InternalKey::compare_encoded).
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.encoded_len());
+        buf.extend_from_slice(self.user_key.as_ref());
+        buf.extend_from_slice(&self.order_key().to_sortable_bytes_desc());
+        buf
+    }
+}
+
+impl InternalKey<Vec<u8>> {
+    /// Decodes bytes built by [`encode()`](Self::encode).
+    pub fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
+        let (user_key, trailer) = split_encoded(buf)?;
+        let (seq, marked) = SeqMarked::from_sortable_bytes_desc(trailer)?.into_parts();
+
+        Ok(Self::new(user_key.to_vec(), InternalSeq::new(seq), marked))
+    }
+
+    /// Compares two encoded internal keys in the same order as [`InternalKey`].
+    ///
+    /// A user key may be a prefix of another one, thus the encoded keys can not be compared
+    /// byte-wise as a whole: the user keys are compared first, then the trailers.
+    pub fn compare_encoded(a: &[u8], b: &[u8]) -> Result<Ordering, DecodeError> {
+        let (a_key, a_trailer) = split_encoded(a)?;
+        let (b_key, b_trailer) = split_encoded(b)?;
+
+        Ok(a_key.cmp(b_key).then_with(|| a_trailer.cmp(b_trailer)))
+    }
+}
+
+/// Splits an encoded internal key into the user key and the trailer.
+fn split_encoded(buf: &[u8]) -> Result<(&[u8], &[u8]), DecodeError> {
+    if buf.len() < TRAILER_LEN {
+        return Err(DecodeError::TooShort {
+            min: TRAILER_LEN,
+            actual: buf.len(),
+        });
+    }
+
+    Ok(buf.split_at(buf.len() - TRAILER_LEN))
+}
+
+impl<K> PartialOrd for InternalKey<K>
+where K: Ord
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K> Ord for InternalKey<K>
+where K: Ord
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.user_key.cmp(&other.user_key).then_with(|| other.order_key().cmp(&self.order_key()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::bb;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    fn nk(key: &str, seq: u64) -> InternalKey<Vec<u8>> {
+        InternalKey::new_normal(bb(key), InternalSeq::new(seq))
+    }
+
+    fn tk(key: &str, seq: u64) -> InternalKey<Vec<u8>> {
+        InternalKey::new_tombstone(bb(key), InternalSeq::new(seq))
+    }
+
+    /// Keys in ascending order.
+    fn sorted_keys() -> Vec<InternalKey<Vec<u8>>> {
+        vec![
+            nk("", 1),
+            tk("a", 3),
+            nk("a", 3),
+            nk("a", 2),
+            tk("a", 0),
+            nk("a", 0),
+            tk("a\0", u64::MAX),
+            nk("ab", 300),
+            nk("ab", 256),
+            nk("ab", 1),
+            nk("b", 5),
+        ]
+    }
+
+    #[test]
+    fn test_accessors() {
+        let k = InternalKey::from_seq_marked("a", &norm(3, 1u64));
+        assert_eq!(k.user_key(), &"a");
+        assert_eq!(k.seq(), InternalSeq::new(3));
+        assert!(!k.is_tombstone());
+        assert_eq!(k.order_key(), norm(3, ()));
+
+        let k = InternalKey::from_seq_marked("a", &ts::<u64>(4));
+        assert!(k.is_tombstone());
+        assert_eq!(k.order_key(), ts(4));
+        assert_eq!(
+            k.into_parts(),
+            ("a", InternalSeq::new(4), Marked::TombStone)
+        );
+    }
+
+    #[test]
+    fn test_ord() {
+        let keys = sorted_keys();
+
+        for (i, a) in keys.iter().enumerate() {
+            for (j, b) in keys.iter().enumerate() {
+                assert_eq!(i.cmp(&j), a.cmp(b), "{:?} vs {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_decode() -> anyhow::Result<()> {
+        let k = tk("ab", 0x0102);
+        let encoded = k.encode();
+        assert_eq!(k.encoded_len(), encoded.len());
+        assert_eq!(encoded, vec![
+            b'a', b'b', 255, 255, 255, 255, 255, 255, 254, 253, 254
+        ]);
+
+        for k in sorted_keys() {
+            assert_eq!(k, InternalKey::decode(&k.encode())?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_encoded() -> anyhow::Result<()> {
+        let keys = sorted_keys();
+
+        for a in &keys {
+            for b in &keys {
+                let got = InternalKey::compare_encoded(&a.encode(), &b.encode())?;
+                assert_eq!(a.cmp(b), got, "{:?} vs {:?}", a, b);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let err = InternalKey::decode(&[1, 2, 3]).unwrap_err();
+        assert_eq!(err, DecodeError::TooShort { min: 9, actual: 3 });
+
+        let err = InternalKey::compare_encoded(&[1, 2, 3], &nk("a", 1).encode()).unwrap_err();
+        assert_eq!(err, DecodeError::TooShort { min: 9, actual: 3 });
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "proptest")]
+mod tests_proptest {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn internal_key() -> impl Strategy<Value = InternalKey> {
+        (
+            proptest::collection::vec(0..3u8, 0..4),
+            any::<SeqMarked<()>>(),
+        )
+            .prop_map(|(k, v)| InternalKey::from_seq_marked(k, &v))
+    }
+
+    proptest! {
+        #[test]
+        fn test_encode(a in internal_key(), b in internal_key()) {
+            let (ea, eb) = (a.encode(), b.encode());
+
+            prop_assert_eq!(ea.len(), a.encoded_len());
+            prop_assert_eq!(&InternalKey::decode(&ea).unwrap(), &a);
+            prop_assert_eq!(InternalKey::compare_encoded(&ea, &eb).unwrap(), a.cmp(&b));
+        }
+    }
+}
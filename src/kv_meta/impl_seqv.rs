@@ -0,0 +1,57 @@
+use crate::Clock;
+use crate::KvMeta;
+use crate::SeqV;
+
+impl<T> SeqV<KvMeta, T> {
+    /// Creates a value expiring at the absolute time `expire_at_ms`.
+    pub fn new_with_expire_at(seq: u64, data: T, expire_at_ms: u64) -> Self {
+        Self::new_with_meta(seq, Some(KvMeta::new_expire_at(expire_at_ms)), data)
+    }
+
+    /// Resolves the meta when the write is proposed at `now_ms`.
+    ///
+    /// A value without meta gets one recording `proposed_at_ms`.
+    /// See [`KvMeta::resolve()`].
+    #[must_use]
+    pub fn resolve_meta(mut self, now_ms: u64) -> Self {
+        self.meta = Some(self.meta.unwrap_or_default().resolve(now_ms));
+        self
+    }
+
+    /// Resolves the meta with the current time of `clock`.
+    #[must_use]
+    pub fn resolve_meta_with(self, clock: &impl Clock) -> Self {
+        self.resolve_meta(clock.now_ms())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManualClock;
+    use crate::SeqValue;
+
+    #[test]
+    fn test_new_with_expire_at() {
+        let sv = SeqV::new_with_expire_at(1, "v", 10);
+        assert_eq!(sv.expires_at_ms_opt(), Some(10));
+        assert!(!sv.is_expired(10));
+        assert!(sv.is_expired(11));
+    }
+
+    #[test]
+    fn test_resolve_meta() {
+        let sv = SeqV::<KvMeta, _>::new(1, "v").resolve_meta(5);
+        assert_eq!(sv.meta, Some(KvMeta::new().with_proposed_at_ms(Some(5))));
+        assert_eq!(sv.expires_at_ms(), u64::MAX);
+
+        let clock = ManualClock::new(5);
+        let sv = SeqV::new_with_meta(1, Some(KvMeta::new_ttl(10)), "v").resolve_meta_with(&clock);
+        assert_eq!(sv.expires_at_ms_opt(), Some(15));
+
+        clock.advance(10);
+        assert!(!sv.is_expired_with(&clock));
+        clock.advance(1);
+        assert!(sv.is_expired_with(&clock));
+    }
+}
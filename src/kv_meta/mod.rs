@@ -0,0 +1,198 @@
+//! A standard meta type for key-value records with expiration.
+
+mod impl_seqv;
+
+use crate::Clock;
+use crate::Expirable;
+
+/// Standard metadata of a key-value record, implementing [`Expirable`].
+///
+/// The expiration time is either an absolute `expire_at_ms`, or a relative `ttl_ms` since the
+/// time the record is proposed. A relative TTL should be resolved to an absolute time when the
+/// record is written, with [`resolve()`](Self::resolve).
+///
+/// ```rust
+/// use seq_marked::KvMeta;
+/// use seq_marked::SeqV;
+/// use seq_marked::SeqValue;
+///
+/// let meta = KvMeta::new_ttl(1_000).resolve(5_000);
+/// assert_eq!(meta.expire_at_ms, Some(6_000));
+///
+/// let sv = SeqV::new_with_meta(1, Some(meta), "v");
+/// assert!(!sv.is_expired(6_000));
+/// assert!(sv.is_expired(6_001));
+/// ```
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "seqv-serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "seq-marked-bincode",
+    derive(bincode::Encode, bincode::Decode)
+)]
+pub struct KvMeta {
+    /// Absolute expiration time in milliseconds since the Unix epoch.
+    pub expire_at_ms: Option<u64>,
+
+    /// Time-to-live in milliseconds, relative to `proposed_at_ms`.
+    pub ttl_ms: Option<u64>,
+
+    /// The time in milliseconds since the Unix epoch when the write is proposed.
+    pub proposed_at_ms: Option<u64>,
+}
+
+impl KvMeta {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a meta expiring at the absolute time `expire_at_ms`.
+    pub fn new_expire_at(expire_at_ms: u64) -> Self {
+        Self::default().with_expire_at_ms(Some(expire_at_ms))
+    }
+
+    /// Creates a meta expiring `ttl_ms` after the write is proposed.
+    pub fn new_ttl(ttl_ms: u64) -> Self {
+        Self::default().with_ttl_ms(Some(ttl_ms))
+    }
+
+    #[must_use]
+    pub fn with_expire_at_ms(mut self, expire_at_ms: Option<u64>) -> Self {
+        self.expire_at_ms = expire_at_ms;
+        self
+    }
+
+    #[must_use]
+    pub fn with_ttl_ms(mut self, ttl_ms: Option<u64>) -> Self {
+        self.ttl_ms = ttl_ms;
+        self
+    }
+
+    #[must_use]
+    pub fn with_proposed_at_ms(mut self, proposed_at_ms: Option<u64>) -> Self {
+        self.proposed_at_ms = proposed_at_ms;
+        self
+    }
+
+    /// Resolves the meta when the write is proposed at `now_ms`.
+    ///
+    /// It sets `proposed_at_ms` if absent, and converts a relative TTL to an absolute
+    /// `expire_at_ms` if no absolute expiration time is set.
+    #[must_use]
+    pub fn resolve(mut self, now_ms: u64) -> Self {
+        let proposed_at = *self.proposed_at_ms.get_or_insert(now_ms);
+
+        if self.expire_at_ms.is_none() {
+            self.expire_at_ms = self.ttl_ms.map(|ttl| proposed_at.saturating_add(ttl));
+        }
+        self
+    }
+
+    /// Resolves the meta with the current time of `clock`.
+    #[must_use]
+    pub fn resolve_with(self, clock: &impl Clock) -> Self {
+        self.resolve(clock.now_ms())
+    }
+}
+
+impl Expirable for KvMeta {
+    /// Returns `expire_at_ms` if set, otherwise `proposed_at_ms + ttl_ms` if both are set.
+    fn expires_at_ms_opt(&self) -> Option<u64> {
+        if self.expire_at_ms.is_some() {
+            return self.expire_at_ms;
+        }
+
+        let proposed_at = self.proposed_at_ms?;
+        let ttl = self.ttl_ms?;
+        Some(proposed_at.saturating_add(ttl))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManualClock;
+
+    #[test]
+    fn test_expires_at() {
+        assert_eq!(KvMeta::new().expires_at_ms_opt(), None);
+        assert_eq!(KvMeta::new_expire_at(10).expires_at_ms_opt(), Some(10));
+
+        // An unresolved TTL does not expire.
+        assert_eq!(KvMeta::new_ttl(10).expires_at_ms_opt(), None);
+
+        let m = KvMeta::new_ttl(10).with_proposed_at_ms(Some(5));
+        assert_eq!(m.expires_at_ms_opt(), Some(15));
+
+        // The absolute time takes precedence.
+        let m = m.with_expire_at_ms(Some(100));
+        assert_eq!(m.expires_at_ms_opt(), Some(100));
+
+        let m = KvMeta::new_ttl(u64::MAX).with_proposed_at_ms(Some(5));
+        assert_eq!(m.expires_at_ms_opt(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let m = KvMeta::new().resolve(5);
+        assert_eq!(m, KvMeta::new().with_proposed_at_ms(Some(5)));
+
+        let m = KvMeta::new_ttl(10).resolve(5);
+        assert_eq!(m.expire_at_ms, Some(15));
+        assert_eq!(m.ttl_ms, Some(10));
+        assert_eq!(m.proposed_at_ms, Some(5));
+
+        // Already resolved: unchanged
+        assert_eq!(m.resolve(100), m);
+
+        let m = KvMeta::new_expire_at(7).with_ttl_ms(Some(10)).resolve(5);
+        assert_eq!(m.expire_at_ms, Some(7));
+
+        let clock = ManualClock::new(20);
+        let m = KvMeta::new_ttl(10).resolve_with(&clock);
+        assert_eq!(m.expire_at_ms, Some(30));
+        assert!(!m.is_expired_with(&clock));
+        clock.set(31);
+        assert!(m.is_expired_with(&clock));
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "seqv-serde")]
+mod tests_serde {
+    use super::*;
+
+    #[test]
+    fn test_kv_meta_serde() {
+        let m = KvMeta::new_ttl(10).resolve(5);
+        let encoded = serde_json::to_string(&m).unwrap();
+        assert_eq!(
+            encoded,
+            r#"{"expire_at_ms":15,"ttl_ms":10,"proposed_at_ms":5}"#
+        );
+
+        let decoded: KvMeta = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(m, decoded);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "seq-marked-bincode")]
+mod tests_bincode {
+    use super::*;
+    use crate::testing::bincode_config;
+
+    #[test]
+    fn test_kv_meta_bincode() {
+        let m = KvMeta::new_ttl(10).resolve(5);
+        let encoded = bincode::encode_to_vec(m, bincode_config()).unwrap();
+        assert_eq!(encoded, vec![1, 15, 1, 10, 1, 5]);
+
+        let (decoded, n): (KvMeta, usize) =
+            bincode::decode_from_slice(&encoded, bincode_config()).unwrap();
+        assert_eq!(n, encoded.len());
+        assert_eq!(m, decoded);
+    }
+}
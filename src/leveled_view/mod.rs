@@ -0,0 +1,317 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Bound;
+use core::ops::RangeBounds;
+
+use crate::MapApi;
+use crate::MapApiRO;
+use crate::SeqMarked;
+use crate::merge::KMerge;
+
+/// A read view stacking a writable top level upon a list of immutable lower levels.
+///
+/// Levels are ordered from the newest to the oldest: `top` first, then `levels[0]`,
+/// `levels[1]`, and so on. Every level must only contain data older than the levels above it.
+///
+/// A key in an upper level shadows the same key in lower levels, and a tombstone hides the key.
+///
+/// ```rust
+/// use seq_marked::BTreeMapApi;
+/// use seq_marked::LeveledView;
+/// use seq_marked::MapApi;
+/// use seq_marked::MapApiRO;
+/// use seq_marked::SeqMarked;
+///
+/// let mut lower = BTreeMapApi::default();
+/// lower.set("a", Some("a1"));
+/// lower.set("b", Some("b1"));
+///
+/// let top = BTreeMapApi::default().with_last_seq(lower.last_seq());
+/// let mut view = LeveledView::new(top, vec![lower]);
+///
+/// view.set("a", None);
+/// assert_eq!(view.get(&"a"), SeqMarked::new_tombstone(3));
+/// assert_eq!(view.get(&"b"), SeqMarked::new_normal(2, "b1"));
+///
+/// let got = view.range_visible(..).collect::<Vec<_>>();
+/// assert_eq!(got, vec![("b", SeqMarked::new_normal(2, "b1"))]);
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct LeveledView<T, L> {
+    top: T,
+    levels: Vec<L>,
+}
+
+impl<T, L> LeveledView<T, L> {
+    /// Creates a view of a writable `top` level and lower `levels`, newest first.
+    pub fn new(top: T, levels: Vec<L>) -> Self {
+        Self { top, levels }
+    }
+
+    pub fn top(&self) -> &T {
+        &self.top
+    }
+
+    pub fn top_mut(&mut self) -> &mut T {
+        &mut self.top
+    }
+
+    /// Returns the immutable lower levels, newest first.
+    pub fn levels(&self) -> &[L] {
+        &self.levels
+    }
+
+    pub fn into_parts(self) -> (T, Vec<L>) {
+        (self.top, self.levels)
+    }
+
+    /// Returns the values of keys in `range` that are not deleted, sorted by key.
+    pub fn range_visible<K, R>(&self, range: R) -> impl Iterator<Item = (K, SeqMarked<T::Data>)>
+    where
+        T: MapApiRO<K>,
+        Self: MapApiRO<K, Data = T::Data>,
+        R: RangeBounds<K>,
+    {
+        MapApiRO::range(self, range).filter(|(_, v)| v.is_normal())
+    }
+}
+
+impl<K, T, L> MapApiRO<K> for LeveledView<T, L>
+where
+    K: Ord + Clone,
+    T: MapApiRO<K>,
+    L: MapApiRO<K, Data = T::Data>,
+{
+    type Data = T::Data;
+
+    /// Returns the value from the topmost level that contains `key`.
+    ///
+    /// With debug assertions, the levels below are checked to hold no newer value of `key`.
+    fn get(&self, key: &K) -> SeqMarked<T::Data> {
+        let v = self.top.get(key);
+        if !v.is_not_found() {
+            self.debug_assert_newest(key, &v, 0);
+            return v;
+        }
+
+        for (i, level) in self.levels.iter().enumerate() {
+            let v = level.get(key);
+            if !v.is_not_found() {
+                self.debug_assert_newest(key, &v, i + 1);
+                return v;
+            }
+        }
+
+        SeqMarked::new_not_found()
+    }
+
+    /// Merges the values of keys in `range` from all levels, including tombstones.
+    fn range<R>(&self, range: R) -> impl Iterator<Item = (K, SeqMarked<T::Data>)>
+    where R: RangeBounds<K> {
+        let bounds: (Bound<K>, Bound<K>) =
+            (range.start_bound().cloned(), range.end_bound().cloned());
+
+        let mut runs = vec![Run::Top(self.top.range(bounds.clone()))];
+        runs.extend(self.levels.iter().map(|level| Run::Level(level.range(bounds.clone()))));
+
+        KMerge::new(runs)
+    }
+}
+
+impl<T, L> LeveledView<T, L> {
+    /// Asserts that no level from `levels[from]` on holds a newer value of `key` than `v`, i.e.,
+    /// the levels are ordered by age as [`range()`](MapApiRO::range) assumes.
+    fn debug_assert_newest<K>(&self, key: &K, v: &SeqMarked<L::Data>, from: usize)
+    where L: MapApiRO<K> {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        for level in &self.levels[from..] {
+            let lower = level.get(key).order_key();
+            debug_assert!(
+                lower <= v.order_key(),
+                "levels must be ordered by age: {:?} in a lower level is newer than {:?}",
+                lower,
+                v.order_key()
+            );
+        }
+    }
+}
+
+/// An iterator over either the top level or a lower level, to merge them without boxing.
+enum Run<A, B> {
+    Top(A),
+    Level(B),
+}
+
+impl<A, B> Iterator for Run<A, B>
+where
+    A: Iterator,
+    B: Iterator<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Run::Top(it) => it.next(),
+            Run::Level(it) => it.next(),
+        }
+    }
+}
+
+impl<K, T, L> MapApi<K> for LeveledView<T, L>
+where
+    K: Ord + Clone,
+    T: MapApi<K>,
+    L: MapApiRO<K, Data = T::Data>,
+{
+    /// Writes to the top level, and returns the previous value visible in this view.
+    fn set(&mut self, key: K, data: Option<T::Data>) -> (SeqMarked<T::Data>, SeqMarked<T::Data>) {
+        let prev = self.get(&key);
+        let (_, result) = self.top.set(key, data);
+        (prev, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BTreeMapApi;
+    use crate::InternalSeq;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    type Level = BTreeMapApi<u64, &'static str>;
+
+    /// Build a view with 3 levels:
+    ///
+    /// ```text
+    /// top:      1=ts       3=c
+    /// level-0:  1=b  2=ts       4=b
+    /// level-1:  1=a  2=a   3=a       5=a
+    /// ```
+    fn sample() -> LeveledView<Level, Level> {
+        let mut l1 = Level::default();
+        l1.set(1, Some("a"));
+        l1.set(2, Some("a"));
+        l1.set(3, Some("a"));
+        l1.set(5, Some("a"));
+
+        let mut l0 = Level::default().with_last_seq(l1.last_seq());
+        l0.set(1, Some("b"));
+        l0.set(2, None);
+        l0.set(4, Some("b"));
+
+        let mut top = Level::default().with_last_seq(l0.last_seq());
+        top.set(1, None);
+        top.set(3, Some("c"));
+
+        LeveledView::new(top, vec![l0, l1])
+    }
+
+    #[test]
+    fn test_accessors() {
+        let v = sample();
+        assert_eq!(v.top().last_seq(), InternalSeq::new(9));
+        assert_eq!(v.levels().len(), 2);
+
+        let (top, levels) = v.into_parts();
+        assert_eq!(top.len(), 2);
+        assert_eq!(levels.len(), 2);
+    }
+
+    #[test]
+    fn test_get() {
+        let v = sample();
+
+        assert_eq!(v.get(&1), ts(8));
+        assert_eq!(v.get(&2), ts(6));
+        assert_eq!(v.get(&3), norm(9, "c"));
+        assert_eq!(v.get(&4), norm(7, "b"));
+        assert_eq!(v.get(&5), norm(4, "a"));
+        assert!(v.get(&6).is_not_found());
+    }
+
+    #[test]
+    fn test_range() {
+        let v = sample();
+
+        let got = MapApiRO::range(&v, ..).collect::<Vec<_>>();
+        assert_eq!(got, vec![
+            (1, ts(8)),
+            (2, ts(6)),
+            (3, norm(9, "c")),
+            (4, norm(7, "b")),
+            (5, norm(4, "a")),
+        ]);
+
+        let got = MapApiRO::range(&v, 2..=4).collect::<Vec<_>>();
+        assert_eq!(got, vec![(2, ts(6)), (3, norm(9, "c")), (4, norm(7, "b"))]);
+    }
+
+    #[test]
+    fn test_range_visible() {
+        let v = sample();
+
+        let got = v.range_visible(..).collect::<Vec<_>>();
+        assert_eq!(got, vec![
+            (3, norm(9, "c")),
+            (4, norm(7, "b")),
+            (5, norm(4, "a"))
+        ]);
+
+        let got = v.range_visible(..3).collect::<Vec<_>>();
+        assert_eq!(got, vec![]);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut v = sample();
+
+        assert_eq!(v.set(5, None), (norm(4, "a"), ts(10)));
+        assert_eq!(v.set(1, Some("d")), (ts(8), norm(11, "d")));
+        assert_eq!(
+            v.set(6, Some("d")),
+            (SeqMarked::new_not_found(), norm(12, "d"))
+        );
+
+        assert_eq!(v.get(&5), ts(10));
+        assert_eq!(v.get(&1), norm(11, "d"));
+
+        let got = v.range_visible(..).collect::<Vec<_>>();
+        assert_eq!(got, vec![
+            (1, norm(11, "d")),
+            (3, norm(9, "c")),
+            (4, norm(7, "b")),
+            (6, norm(12, "d")),
+        ]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "levels must be ordered by age")]
+    fn test_get_levels_out_of_order() {
+        let mut upper = Level::default();
+        upper.set(1, Some("old"));
+
+        let mut lower = Level::default().with_last_seq(InternalSeq::new(5));
+        lower.set(1, Some("new"));
+
+        let v = LeveledView::new(Level::default(), vec![upper, lower]);
+        v.get(&1);
+    }
+
+    #[test]
+    fn test_no_lower_level() {
+        let mut v = LeveledView::<Level, Level>::new(Level::default(), vec![]);
+
+        v.set(1, Some("a"));
+        assert_eq!(v.get(&1), norm(1, "a"));
+        assert_eq!(v.range_visible(..).collect::<Vec<_>>(), vec![(
+            1,
+            norm(1, "a")
+        )]);
+    }
+}
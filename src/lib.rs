@@ -19,21 +19,88 @@
 //! assert!(v1 < v2); // ordered by sequence
 //! assert!(v2 < v2_ts); // ordered by tombstone > normal
 //! ```
+//!
+//! ## `no_std`
+//!
+//! Without the default feature `std`, the crate is `no_std` and requires `alloc`.
+
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+extern crate alloc;
 
+pub mod block;
+mod bloom_filter;
+mod change;
+mod clock;
+mod codec;
+pub mod compaction_filter;
+mod errors;
 mod expirable;
+mod expire_index;
+mod internal_key;
+mod kv_meta;
+mod leveled_view;
+mod map_api;
 mod marked;
+mod match_seq;
+pub mod merge;
+mod range_tombstone;
+mod seq_allocator;
 mod seq_data;
 mod seq_marked;
 mod seq_value_trait;
 mod seqv;
+#[cfg(feature = "std")]
+mod snapshot_list;
+#[cfg(feature = "sst")]
+pub mod sst;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+mod versioned_map;
+#[cfg(feature = "wal")]
+pub mod wal;
+mod write_batch;
 
-#[cfg(test)]
-pub(crate) mod testing;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
+pub use bloom_filter::BloomFilter;
+pub use bloom_filter::BloomFilterBuilder;
+pub use change::Change;
+pub use clock::Clock;
+pub use clock::ManualClock;
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
+pub use compaction_filter::CompactionFilter;
+pub use errors::ConversionError;
+pub use errors::DecodeError;
 pub use expirable::Expirable;
+pub use expire_index::ExpireIndex;
+pub use internal_key::InternalKey;
+pub use kv_meta::KvMeta;
+pub use leveled_view::LeveledView;
+pub use map_api::BTreeMapApi;
+pub use map_api::MapApi;
+pub use map_api::MapApiRO;
+pub use map_api::MapApiSnapshot;
 pub use marked::Marked;
+pub use match_seq::MatchSeq;
+pub use match_seq::MatchSeqError;
+pub use range_tombstone::FragmentedRangeTombstones;
+pub use range_tombstone::RangeTombstone;
+pub use range_tombstone::RangeTombstoneFragment;
+#[cfg(feature = "std")]
+pub use seq_allocator::SeqAllocator;
+pub use seq_allocator::SeqRange;
 pub use seq_data::SeqData;
 pub use seq_marked::InternalSeq;
 pub use seq_marked::SeqMarked;
 pub use seq_value_trait::SeqValue;
 pub use seqv::SeqV;
+#[cfg(feature = "std")]
+pub use snapshot_list::Snapshot;
+#[cfg(feature = "std")]
+pub use snapshot_list::SnapshotList;
+pub use versioned_map::VersionedMap;
+pub use write_batch::CommittedBatch;
+pub use write_batch::WriteBatch;
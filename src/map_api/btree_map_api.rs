@@ -0,0 +1,173 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+use crate::InternalSeq;
+use crate::MapApi;
+use crate::MapApiRO;
+use crate::SeqMarked;
+
+/// Reference implementation of [`MapApi`] backed by a [`BTreeMap`].
+///
+/// It keeps only the latest value of every key, and allocates seq for writes by itself.
+///
+/// ```rust
+/// use seq_marked::BTreeMapApi;
+/// use seq_marked::MapApi;
+/// use seq_marked::MapApiRO;
+/// use seq_marked::SeqMarked;
+///
+/// let mut m = BTreeMapApi::default();
+///
+/// let (prev, result) = m.set("a", Some("v1"));
+/// assert!(prev.is_not_found());
+/// assert_eq!(result, SeqMarked::new_normal(1, "v1"));
+///
+/// m.set("a", None);
+/// assert_eq!(m.get(&"a"), SeqMarked::new_tombstone(2));
+/// assert!(m.get(&"b").is_not_found());
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct BTreeMapApi<K, D = Vec<u8>> {
+    /// The seq of the last write.
+    last_seq: InternalSeq,
+    map: BTreeMap<K, SeqMarked<D>>,
+}
+
+impl<K, D> Default for BTreeMapApi<K, D> {
+    fn default() -> Self {
+        Self {
+            last_seq: InternalSeq::default(),
+            map: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K, D> BTreeMapApi<K, D> {
+    /// Sets the seq of the last write; the next write uses `last_seq + 1`.
+    ///
+    /// A map stacked upon other stores must start after the greatest seq in them.
+    #[must_use]
+    pub fn with_last_seq(mut self, last_seq: InternalSeq) -> Self {
+        self.last_seq = last_seq;
+        self
+    }
+
+    /// Returns the seq of the last write.
+    pub fn last_seq(&self) -> InternalSeq {
+        self.last_seq
+    }
+
+    /// Returns the number of keys, including deleted ones.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Consumes self and returns the underlying map.
+    pub fn into_inner(self) -> BTreeMap<K, SeqMarked<D>> {
+        self.map
+    }
+}
+
+impl<K, D> MapApiRO<K> for BTreeMapApi<K, D>
+where
+    K: Ord + Clone,
+    D: Clone,
+{
+    type Data = D;
+
+    fn get(&self, key: &K) -> SeqMarked<D> {
+        self.map.get(key).cloned().unwrap_or_else(SeqMarked::new_not_found)
+    }
+
+    fn range<R>(&self, range: R) -> impl Iterator<Item = (K, SeqMarked<D>)>
+    where R: RangeBounds<K> {
+        self.map.range(range).map(|(k, v)| (k.clone(), v.clone()))
+    }
+}
+
+impl<K, D> MapApi<K> for BTreeMapApi<K, D>
+where
+    K: Ord + Clone,
+    D: Clone,
+{
+    fn set(&mut self, key: K, data: Option<D>) -> (SeqMarked<D>, SeqMarked<D>) {
+        self.last_seq += 1;
+
+        let result = match data {
+            Some(d) => SeqMarked::new_normal(*self.last_seq, d),
+            None => SeqMarked::new_tombstone(*self.last_seq),
+        };
+
+        let prev = self.map.insert(key, result.clone());
+        (prev.unwrap_or_else(SeqMarked::new_not_found), result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    #[test]
+    fn test_default() {
+        let m = BTreeMapApi::<u64, u64>::default();
+        assert_eq!(m.last_seq(), InternalSeq::new(0));
+        assert!(m.is_empty());
+        assert!(m.get(&1).is_not_found());
+        assert_eq!(m.range(..).count(), 0);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut m = BTreeMapApi::default();
+
+        assert_eq!(
+            m.set(1, Some("a")),
+            (SeqMarked::new_not_found(), norm(1, "a"))
+        );
+        assert_eq!(m.set(1, Some("b")), (norm(1, "a"), norm(2, "b")));
+        assert_eq!(m.set(1, None), (norm(2, "b"), ts(3)));
+        assert_eq!(m.set(1, None), (ts(3), ts(4)));
+        assert_eq!(m.set(2, None), (SeqMarked::new_not_found(), ts(5)));
+
+        assert_eq!(m.last_seq(), InternalSeq::new(5));
+        assert_eq!(m.len(), 2);
+        assert_eq!(m.get(&1), ts(4));
+    }
+
+    #[test]
+    fn test_with_last_seq() {
+        let mut m = BTreeMapApi::default().with_last_seq(InternalSeq::new(10));
+
+        assert_eq!(
+            m.set(1, Some("a")),
+            (SeqMarked::new_not_found(), norm(11, "a"))
+        );
+        assert_eq!(m.last_seq(), InternalSeq::new(11));
+    }
+
+    #[test]
+    fn test_range() {
+        let mut m = BTreeMapApi::default();
+        m.set(3, Some("c"));
+        m.set(1, Some("a"));
+        m.set(2, Some("b"));
+        m.set(2, None);
+
+        let got = m.range(..).collect::<Vec<_>>();
+        assert_eq!(got, vec![(1, norm(2, "a")), (2, ts(4)), (3, norm(1, "c"))]);
+
+        let got = m.range(2..3).collect::<Vec<_>>();
+        assert_eq!(got, vec![(2, ts(4))]);
+
+        assert_eq!(m.into_inner().len(), 3);
+    }
+}
@@ -0,0 +1,60 @@
+//! Key-value store API over [`SeqMarked`] values, shared by different backends.
+
+mod btree_map_api;
+
+use core::ops::RangeBounds;
+
+pub use btree_map_api::BTreeMapApi;
+
+use crate::InternalSeq;
+use crate::SeqMarked;
+
+/// Read-only API of a key-value store whose values are [`SeqMarked`].
+///
+/// A deleted key is returned as a tombstone, so that a store can be stacked upon another one and
+/// shadow it; an absent key is returned as [`SeqMarked::new_not_found()`].
+pub trait MapApiRO<K> {
+    /// The type of the data stored in a value.
+    type Data;
+
+    /// Returns the value of `key`.
+    ///
+    /// A deleted key returns a tombstone; an absent key returns [`SeqMarked::new_not_found()`].
+    fn get(&self, key: &K) -> SeqMarked<Self::Data>;
+
+    /// Returns the values of keys in `range`, sorted by key, including tombstones.
+    fn range<R>(&self, range: R) -> impl Iterator<Item = (K, SeqMarked<Self::Data>)>
+    where R: RangeBounds<K>;
+}
+
+/// Read-write API of a key-value store whose values are [`SeqMarked`].
+pub trait MapApi<K>: MapApiRO<K> {
+    /// Writes `data` to `key` with a new seq, or a tombstone if `data` is `None`.
+    ///
+    /// Returns the values of `key` before and after the write.
+    fn set(
+        &mut self,
+        key: K,
+        data: Option<Self::Data>,
+    ) -> (SeqMarked<Self::Data>, SeqMarked<Self::Data>);
+}
+
+/// Read-only API of a key-value store that keeps older versions, to read at a snapshot.
+pub trait MapApiSnapshot<K>: MapApiRO<K> {
+    /// Returns the value of `key` visible at `snapshot`, i.e., the newest version with seq `<=
+    /// snapshot`.
+    ///
+    /// A deleted key returns a tombstone; a key without a visible version returns
+    /// [`SeqMarked::new_not_found()`].
+    fn get_at(&self, key: &K, snapshot: InternalSeq) -> SeqMarked<Self::Data>;
+
+    /// Returns the values of keys in `range` visible at `snapshot`, sorted by key, including
+    /// tombstones.
+    fn range_at<R>(
+        &self,
+        range: R,
+        snapshot: InternalSeq,
+    ) -> impl Iterator<Item = (K, SeqMarked<Self::Data>)>
+    where
+        R: RangeBounds<K>;
+}
@@ -1,20 +1,17 @@
-use std::io;
+use alloc::string::String;
+use alloc::vec::Vec;
 
+use crate::ConversionError;
 use crate::Marked;
 
 impl<M> TryFrom<Marked<(Option<M>, Vec<u8>)>> for Marked<(Option<M>, String)> {
-    type Error = io::Error;
+    type Error = ConversionError;
 
     fn try_from(marked: Marked<(Option<M>, Vec<u8>)>) -> Result<Self, Self::Error> {
         match marked {
             Marked::TombStone => Ok(Marked::TombStone),
             Marked::Normal((meta, value)) => {
-                let s = String::from_utf8(value).map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!("fail to convert Vec<u8> to String: {}", e),
-                    )
-                })?;
+                let s = String::from_utf8(value)?;
 
                 Ok(Marked::Normal((meta, s)))
             }
@@ -80,7 +77,8 @@ mod tests {
 
         assert!(result.is_err());
         let error = result.unwrap_err();
-        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.seq(), None);
+        assert_eq!(error.bytes(), &[0xFF, 0xFE, 0xFD]);
         assert!(error.to_string().contains("fail to convert Vec<u8> to String"));
     }
 
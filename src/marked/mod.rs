@@ -118,3 +118,19 @@ mod tests_serde {
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "proptest")]
+mod tests_proptest {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_tombstone_greatest(a in any::<Marked<u64>>(), d in any::<u64>()) {
+            prop_assert!(Marked::TombStone >= a);
+            prop_assert!(Marked::Normal(d) <= Marked::TombStone);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use core::error::Error;
+use core::fmt;
+
+use crate::MatchSeq;
+
+/// The seq of the current value does not match the condition of a write.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "seqv-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchSeqError {
+    /// The condition required by the write.
+    pub expected: MatchSeq,
+
+    /// The seq of the current value, 0 if it is absent.
+    pub actual: u64,
+}
+
+impl MatchSeqError {
+    pub fn new(expected: MatchSeq, actual: u64) -> Self {
+        Self { expected, actual }
+    }
+}
+
+impl fmt::Display for MatchSeqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "seq mismatch: expected seq {}, but actual seq is {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl Error for MatchSeqError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let e = MatchSeqError::new(MatchSeq::Exact(3), 5);
+        assert_eq!(
+            e.to_string(),
+            "seq mismatch: expected seq == 3, but actual seq is 5"
+        );
+
+        let e = MatchSeqError::new(MatchSeq::NotExists, 2);
+        assert_eq!(
+            e.to_string(),
+            "seq mismatch: expected seq not exists, but actual seq is 2"
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Conditions on the seq of the current value for compare-and-swap writes.
+
+mod match_seq_error;
+
+use core::fmt;
+
+pub use match_seq_error::MatchSeqError;
+
+use crate::SeqValue;
+
+/// Condition a write requires on the seq of the current value.
+///
+/// An absent value has seq 0, as returned by [`SeqValue::seq()`] for `None::<SeqV>` and for a
+/// tombstone [`SeqMarked`](crate::SeqMarked), whose `user_seq()` is 0.
+///
+/// ```rust
+/// use seq_marked::MatchSeq;
+/// use seq_marked::SeqMarked;
+/// use seq_marked::SeqV;
+///
+/// let current = Some(SeqV::<(), _>::new(3, "v"));
+/// assert!(MatchSeq::Exact(3).match_seq(&current).is_ok());
+/// assert!(MatchSeq::NotExists.match_seq(&current).is_err());
+///
+/// let deleted = SeqMarked::<(Option<()>, &str)>::new_tombstone(5);
+/// assert!(MatchSeq::NotExists.match_seq(&deleted).is_ok());
+/// ```
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "seqv-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatchSeq {
+    /// Matches any value, present or absent.
+    Any,
+
+    /// Matches a value whose seq equals the given seq.
+    ///
+    /// `Exact(0)` matches only an absent value.
+    Exact(u64),
+
+    /// Matches a value whose seq is greater than or equal to the given seq.
+    ///
+    /// `GE(1)` matches any present value.
+    GE(u64),
+
+    /// Matches only an absent value.
+    NotExists,
+}
+
+impl MatchSeq {
+    /// Returns `true` if a value with `seq` matches; seq 0 means the value is absent.
+    pub fn matches(&self, seq: u64) -> bool {
+        match *self {
+            MatchSeq::Any => true,
+            MatchSeq::Exact(s) => seq == s,
+            MatchSeq::GE(s) => seq >= s,
+            MatchSeq::NotExists => seq == 0,
+        }
+    }
+
+    /// Checks if the `current` value matches this condition.
+    pub fn match_seq<M, V>(&self, current: &impl SeqValue<M, V>) -> Result<(), MatchSeqError> {
+        let seq = current.seq();
+
+        if self.matches(seq) {
+            Ok(())
+        } else {
+            Err(MatchSeqError::new(*self, seq))
+        }
+    }
+}
+
+impl fmt::Display for MatchSeq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchSeq::Any => write!(f, "is any value"),
+            MatchSeq::Exact(s) => write!(f, "== {}", s),
+            MatchSeq::GE(s) => write!(f, ">= {}", s),
+            MatchSeq::NotExists => write!(f, "not exists"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SeqMarked;
+    use crate::SeqV;
+
+    #[test]
+    fn test_matches() {
+        assert!(MatchSeq::Any.matches(0));
+        assert!(MatchSeq::Any.matches(5));
+
+        assert!(MatchSeq::Exact(0).matches(0));
+        assert!(!MatchSeq::Exact(0).matches(5));
+        assert!(MatchSeq::Exact(5).matches(5));
+        assert!(!MatchSeq::Exact(5).matches(6));
+
+        assert!(!MatchSeq::GE(1).matches(0));
+        assert!(MatchSeq::GE(1).matches(1));
+        assert!(MatchSeq::GE(5).matches(6));
+        assert!(!MatchSeq::GE(5).matches(4));
+
+        assert!(MatchSeq::NotExists.matches(0));
+        assert!(!MatchSeq::NotExists.matches(1));
+    }
+
+    #[test]
+    fn test_match_seq_v() {
+        let current = SeqV::<(), _>::new(5, 1u64);
+
+        assert!(MatchSeq::Any.match_seq(&current).is_ok());
+        assert!(MatchSeq::Exact(5).match_seq(&current).is_ok());
+        assert!(MatchSeq::GE(5).match_seq(&current).is_ok());
+
+        assert_eq!(
+            MatchSeq::Exact(4).match_seq(&current),
+            Err(MatchSeqError::new(MatchSeq::Exact(4), 5))
+        );
+        assert_eq!(
+            MatchSeq::NotExists.match_seq(&current),
+            Err(MatchSeqError::new(MatchSeq::NotExists, 5))
+        );
+    }
+
+    #[test]
+    fn test_match_option_seq_v() {
+        let absent = None::<SeqV<(), u64>>;
+
+        assert!(MatchSeq::Any.match_seq(&absent).is_ok());
+        assert!(MatchSeq::NotExists.match_seq(&absent).is_ok());
+        assert!(MatchSeq::Exact(0).match_seq(&absent).is_ok());
+        assert_eq!(
+            MatchSeq::GE(1).match_seq(&absent),
+            Err(MatchSeqError::new(MatchSeq::GE(1), 0))
+        );
+
+        let present = Some(SeqV::<(), _>::new(3, 1u64));
+        assert!(MatchSeq::GE(1).match_seq(&present).is_ok());
+        assert!(MatchSeq::NotExists.match_seq(&present).is_err());
+    }
+
+    #[test]
+    fn test_match_seq_marked_tombstone_does_not_exist() {
+        let tombstone = SeqMarked::<(Option<()>, u64)>::new_tombstone(7);
+
+        assert!(MatchSeq::NotExists.match_seq(&tombstone).is_ok());
+        assert!(MatchSeq::Exact(0).match_seq(&tombstone).is_ok());
+        assert_eq!(
+            MatchSeq::Exact(7).match_seq(&tombstone),
+            Err(MatchSeqError::new(MatchSeq::Exact(7), 0))
+        );
+        assert!(MatchSeq::GE(1).match_seq(&tombstone).is_err());
+
+        let normal = SeqMarked::<(Option<()>, u64)>::new_normal(7, (None, 1));
+        assert!(MatchSeq::Exact(7).match_seq(&normal).is_ok());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(MatchSeq::Any.to_string(), "is any value");
+        assert_eq!(MatchSeq::Exact(3).to_string(), "== 3");
+        assert_eq!(MatchSeq::GE(3).to_string(), ">= 3");
+        assert_eq!(MatchSeq::NotExists.to_string(), "not exists");
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "seqv-serde")]
+mod tests_serde {
+    use super::*;
+
+    #[test]
+    fn test_serde() {
+        for m in [
+            MatchSeq::Any,
+            MatchSeq::Exact(3),
+            MatchSeq::GE(4),
+            MatchSeq::NotExists,
+        ] {
+            let json = serde_json::to_string(&m).unwrap();
+            let decoded: MatchSeq = serde_json::from_str(&json).unwrap();
+            assert_eq!(m, decoded);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&MatchSeq::Exact(3)).unwrap(),
+            r#"{"Exact":3}"#
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use core::cmp::Ordering;
+
+use crate::SeqMarked;
+
+/// An entry in the merge heap, tagged with the index of the run it is read from.
+///
+/// Ordered so that a max-heap pops the smallest key first, then the greatest
+/// [`SeqMarked::order_key`], then the entry from the run with the smallest index.
+pub(crate) struct HeapEntry<K, D> {
+    pub(crate) key: K,
+    pub(crate) value: SeqMarked<D>,
+    pub(crate) run: usize,
+}
+
+impl<K, D> PartialEq for HeapEntry<K, D>
+where K: Ord
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K, D> Eq for HeapEntry<K, D> where K: Ord {}
+
+impl<K, D> PartialOrd for HeapEntry<K, D>
+where K: Ord
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, D> Ord for HeapEntry<K, D>
+where K: Ord
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .key
+            .cmp(&self.key)
+            .then_with(|| self.value.order_key().cmp(&other.value.order_key()))
+            .then_with(|| other.run.cmp(&self.run))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BinaryHeap;
+
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    fn entry(key: u64, value: SeqMarked<u64>, run: usize) -> HeapEntry<u64, u64> {
+        HeapEntry { key, value, run }
+    }
+
+    #[test]
+    fn test_heap_order() {
+        let mut heap = BinaryHeap::new();
+        heap.push(entry(2, norm(9, 0), 0));
+        heap.push(entry(1, norm(3, 0), 0));
+        heap.push(entry(1, norm(5, 0), 1));
+        heap.push(entry(1, ts(5), 2));
+        heap.push(entry(1, ts(5), 1));
+
+        let popped =
+            core::iter::from_fn(|| heap.pop()).map(|e| (e.key, e.value, e.run)).collect::<Vec<_>>();
+
+        assert_eq!(popped, vec![
+            (1, ts(5), 1),
+            (1, ts(5), 2),
+            (1, norm(5, 0), 1),
+            (1, norm(3, 0), 0),
+            (2, norm(9, 0), 0),
+        ]);
+    }
+}
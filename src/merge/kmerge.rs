@@ -0,0 +1,196 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+use crate::SeqMarked;
+use crate::merge::heap_entry::HeapEntry;
+
+/// Merges several sorted runs of `(K, SeqMarked<D>)` into one sorted stream.
+///
+/// Every run must be sorted by key in ascending order, and versions of the same key in a run must
+/// be sorted by [`SeqMarked::order_key`] in descending order, i.e., newest first.
+///
+/// By default only the winner of every key is yielded, which is the version with the greatest
+/// [`SeqMarked::order_key`]. Thus at the same seq a tombstone wins over a normal value. If two
+/// runs contain versions with an equal order key, the one from the run listed first wins.
+///
+/// With [`with_all_versions(true)`](Self::with_all_versions), every version is yielded, sorted by
+/// key ascending and then by order key descending.
+///
+/// ```rust
+/// use seq_marked::SeqMarked;
+/// use seq_marked::merge::KMerge;
+///
+/// let a = vec![(1, SeqMarked::new_normal(1, "a1")), (3, SeqMarked::new_normal(3, "a3"))];
+/// let b = vec![(1, SeqMarked::new_tombstone(2)), (2, SeqMarked::new_normal(4, "b2"))];
+///
+/// let got = KMerge::new([a.into_iter(), b.into_iter()]).collect::<Vec<_>>();
+/// assert_eq!(got, vec![
+///     (1, SeqMarked::new_tombstone(2)),
+///     (2, SeqMarked::new_normal(4, "b2")),
+///     (3, SeqMarked::new_normal(3, "a3")),
+/// ]);
+/// ```
+pub struct KMerge<K, D, I> {
+    runs: Vec<I>,
+    heap: BinaryHeap<HeapEntry<K, D>>,
+    all_versions: bool,
+}
+
+impl<K, D, I> KMerge<K, D, I>
+where
+    K: Ord,
+    I: Iterator<Item = (K, SeqMarked<D>)>,
+{
+    /// Creates a merge of the given runs, listed in the order of tie-breaking priority.
+    pub fn new(runs: impl IntoIterator<Item = I>) -> Self {
+        let mut runs = runs.into_iter().collect::<Vec<_>>();
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+
+        for (run, it) in runs.iter_mut().enumerate() {
+            if let Some((key, value)) = it.next() {
+                heap.push(HeapEntry { key, value, run });
+            }
+        }
+
+        Self {
+            runs,
+            heap,
+            all_versions: false,
+        }
+    }
+
+    /// Sets whether to yield every version of a key, or only the winner.
+    #[must_use]
+    pub fn with_all_versions(mut self, all_versions: bool) -> Self {
+        self.all_versions = all_versions;
+        self
+    }
+
+    /// Pops the smallest entry and refills the heap from the run it belongs to.
+    fn pop(&mut self) -> Option<HeapEntry<K, D>> {
+        let entry = self.heap.pop()?;
+
+        if let Some((key, value)) = self.runs[entry.run].next() {
+            self.heap.push(HeapEntry {
+                key,
+                value,
+                run: entry.run,
+            });
+        }
+
+        Some(entry)
+    }
+}
+
+impl<K, D, I> Iterator for KMerge<K, D, I>
+where
+    K: Ord,
+    I: Iterator<Item = (K, SeqMarked<D>)>,
+{
+    type Item = (K, SeqMarked<D>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.pop()?;
+
+        if !self.all_versions {
+            while self.heap.peek().is_some_and(|top| top.key == entry.key) {
+                self.pop();
+            }
+        }
+
+        Some((entry.key, entry.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    type Run = alloc::vec::IntoIter<(u64, SeqMarked<&'static str>)>;
+
+    fn runs(runs: Vec<Vec<(u64, SeqMarked<&'static str>)>>) -> Vec<Run> {
+        runs.into_iter().map(|r| r.into_iter()).collect()
+    }
+
+    #[test]
+    fn test_merge_empty() {
+        let got = KMerge::new(runs(vec![])).collect::<Vec<_>>();
+        assert!(got.is_empty());
+
+        let got = KMerge::new(runs(vec![vec![], vec![]])).collect::<Vec<_>>();
+        assert!(got.is_empty());
+    }
+
+    #[test]
+    fn test_merge_latest() {
+        let input = runs(vec![
+            vec![(1, norm(1, "a")), (3, norm(3, "a")), (5, ts(5))],
+            vec![(1, norm(2, "b")), (2, norm(2, "b")), (5, norm(4, "b"))],
+            vec![(3, norm(1, "c")), (4, ts(7))],
+        ]);
+
+        let got = KMerge::new(input).collect::<Vec<_>>();
+        assert_eq!(got, vec![
+            (1, norm(2, "b")),
+            (2, norm(2, "b")),
+            (3, norm(3, "a")),
+            (4, ts(7)),
+            (5, ts(5)),
+        ]);
+    }
+
+    #[test]
+    fn test_merge_tombstone_wins_at_equal_seq() {
+        let input = runs(vec![vec![(1, norm(3, "a"))], vec![(1, ts(3))]]);
+
+        let got = KMerge::new(input).collect::<Vec<_>>();
+        assert_eq!(got, vec![(1, ts(3))]);
+    }
+
+    #[test]
+    fn test_merge_equal_order_key_prefers_earlier_run() {
+        let input = runs(vec![vec![(1, norm(3, "a"))], vec![(1, norm(3, "b"))]]);
+        let got = KMerge::new(input).collect::<Vec<_>>();
+        assert_eq!(got, vec![(1, norm(3, "a"))]);
+
+        let input = runs(vec![vec![(1, norm(3, "b"))], vec![(1, norm(3, "a"))]]);
+        let got = KMerge::new(input).collect::<Vec<_>>();
+        assert_eq!(got, vec![(1, norm(3, "b"))]);
+    }
+
+    #[test]
+    fn test_merge_versions_in_one_run() {
+        let input = runs(vec![
+            vec![
+                (1, norm(5, "a")),
+                (1, norm(2, "a")),
+                (2, ts(4)),
+                (2, norm(1, "a")),
+            ],
+            vec![(1, norm(3, "b")), (2, norm(3, "b"))],
+        ]);
+
+        let got = KMerge::new(input).collect::<Vec<_>>();
+        assert_eq!(got, vec![(1, norm(5, "a")), (2, ts(4))]);
+    }
+
+    #[test]
+    fn test_merge_all_versions() {
+        let input = runs(vec![
+            vec![(1, norm(5, "a")), (1, norm(2, "a")), (2, ts(4))],
+            vec![(1, norm(3, "b")), (2, norm(4, "b")), (3, norm(1, "b"))],
+        ]);
+
+        let got = KMerge::new(input).with_all_versions(true).collect::<Vec<_>>();
+        assert_eq!(got, vec![
+            (1, norm(5, "a")),
+            (1, norm(3, "b")),
+            (1, norm(2, "a")),
+            (2, ts(4)),
+            (2, norm(4, "b")),
+            (3, norm(1, "b")),
+        ]);
+    }
+}
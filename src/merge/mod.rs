@@ -0,0 +1,11 @@
+//! K-way merge of sorted runs of `(K, SeqMarked<D>)`.
+//!
+//! The winner of a key is decided by [`SeqMarked::order_key`], the same ordering as
+//! [`SeqMarked::max`], so that a tombstone wins over a normal value with the same seq.
+
+mod heap_entry;
+mod kmerge;
+
+#[cfg(doc)]
+use crate::SeqMarked;
+pub use crate::merge::kmerge::KMerge;
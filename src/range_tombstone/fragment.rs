@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use crate::InternalSeq;
+
+/// A key range `[start, end)` covered by the same set of range tombstones.
+///
+/// Built by [`FragmentedRangeTombstones`](crate::FragmentedRangeTombstones).
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct RangeTombstoneFragment<K> {
+    start: K,
+    end: K,
+
+    /// Seqs of the range tombstones covering this fragment, in descending order.
+    seqs: Vec<InternalSeq>,
+}
+
+impl<K> RangeTombstoneFragment<K> {
+    /// Creates a fragment, `seqs` must be non-empty and sorted in descending order.
+    pub(crate) fn new(start: K, end: K, seqs: Vec<InternalSeq>) -> Self {
+        debug_assert!(!seqs.is_empty());
+        debug_assert!(seqs.windows(2).all(|w| w[0] > w[1]));
+
+        Self { start, end, seqs }
+    }
+
+    /// Returns the inclusive start of the fragment.
+    pub fn start(&self) -> &K {
+        &self.start
+    }
+
+    /// Returns the exclusive end of the fragment.
+    pub fn end(&self) -> &K {
+        &self.end
+    }
+
+    /// Returns the seqs of the range tombstones covering this fragment, newest first.
+    pub fn seqs(&self) -> &[InternalSeq] {
+        &self.seqs
+    }
+
+    /// Returns the seq of the newest range tombstone covering this fragment.
+    pub fn max_seq(&self) -> InternalSeq {
+        self.seqs[0]
+    }
+
+    /// Returns the seq of the newest range tombstone visible at `snapshot`.
+    pub fn max_seq_at(&self, snapshot: InternalSeq) -> Option<InternalSeq> {
+        self.seqs.iter().copied().find(|s| *s <= snapshot)
+    }
+
+    /// Returns `true` if `key` is in `[start, end)`.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.start.borrow() <= key && key < self.end.borrow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(seq: u64) -> InternalSeq {
+        InternalSeq::new(seq)
+    }
+
+    #[test]
+    fn test_fragment() {
+        let f = RangeTombstoneFragment::new(1, 5, vec![s(7), s(3), s(2)]);
+
+        assert_eq!(f.start(), &1);
+        assert_eq!(f.end(), &5);
+        assert_eq!(f.seqs(), &[s(7), s(3), s(2)]);
+        assert_eq!(f.max_seq(), s(7));
+
+        assert!(f.contains(&1));
+        assert!(!f.contains(&5));
+    }
+
+    #[test]
+    fn test_max_seq_at() {
+        let f = RangeTombstoneFragment::new(1, 5, vec![s(7), s(3)]);
+
+        assert_eq!(f.max_seq_at(s(10)), Some(s(7)));
+        assert_eq!(f.max_seq_at(s(7)), Some(s(7)));
+        assert_eq!(f.max_seq_at(s(6)), Some(s(3)));
+        assert_eq!(f.max_seq_at(s(2)), None);
+    }
+}
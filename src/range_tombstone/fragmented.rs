@@ -0,0 +1,282 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use crate::InternalSeq;
+use crate::RangeTombstone;
+use crate::RangeTombstoneFragment;
+use crate::SeqMarked;
+
+/// A set of range tombstones split into sorted, non-overlapping fragments.
+///
+/// Every fragment records all the range tombstones covering it, so that reads at an older
+/// snapshot can still find the range tombstones visible to them.
+///
+/// ```rust
+/// use seq_marked::FragmentedRangeTombstones;
+/// use seq_marked::InternalSeq;
+/// use seq_marked::RangeTombstone;
+///
+/// let rts = FragmentedRangeTombstones::new([
+///     RangeTombstone::new(1, 5, InternalSeq::new(3)),
+///     RangeTombstone::new(3, 8, InternalSeq::new(6)),
+/// ]);
+///
+/// assert_eq!(rts.fragments().len(), 3);
+/// assert!(rts.covers(&2, InternalSeq::new(2)));
+/// assert!(!rts.covers(&2, InternalSeq::new(4)));
+/// assert!(rts.covers(&4, InternalSeq::new(4)));
+/// assert!(!rts.covers(&8, InternalSeq::new(4)));
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct FragmentedRangeTombstones<K> {
+    fragments: Vec<RangeTombstoneFragment<K>>,
+}
+
+impl<K> Default for FragmentedRangeTombstones<K> {
+    fn default() -> Self {
+        Self {
+            fragments: Vec::new(),
+        }
+    }
+}
+
+impl<K> FragmentedRangeTombstones<K>
+where K: Ord + Clone
+{
+    /// Splits possibly overlapping range tombstones into non-overlapping fragments.
+    ///
+    /// Empty ranges, i.e., `start >= end`, are ignored.
+    pub fn new(tombstones: impl IntoIterator<Item = RangeTombstone<K>>) -> Self {
+        let mut tombstones = tombstones
+            .into_iter()
+            .filter(|t| t.start() < t.end())
+            .map(|t| t.into_parts())
+            .collect::<Vec<_>>();
+        tombstones.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut bounds = Vec::with_capacity(tombstones.len() * 2);
+        for (start, end, _) in &tombstones {
+            bounds.push(start.clone());
+            bounds.push(end.clone());
+        }
+        bounds.sort();
+        bounds.dedup();
+
+        let mut fragments = Vec::new();
+
+        // Range tombstones covering the current fragment, as `(end, seq)`.
+        let mut active: Vec<(K, InternalSeq)> = Vec::new();
+        let mut pending = tombstones.into_iter().peekable();
+
+        for w in bounds.windows(2) {
+            let (lo, hi) = (&w[0], &w[1]);
+
+            active.retain(|(end, _)| end > lo);
+            while let Some((_, end, seq)) = pending.next_if(|(start, _, _)| start <= lo) {
+                active.push((end, seq));
+            }
+
+            if active.is_empty() {
+                continue;
+            }
+
+            let mut seqs = active.iter().map(|(_, seq)| *seq).collect::<Vec<_>>();
+            seqs.sort_by(|a, b| b.cmp(a));
+            seqs.dedup();
+
+            fragments.push(RangeTombstoneFragment::new(lo.clone(), hi.clone(), seqs));
+        }
+
+        Self { fragments }
+    }
+}
+
+impl<K> FragmentedRangeTombstones<K> {
+    /// Returns the fragments sorted by key.
+    pub fn fragments(&self) -> &[RangeTombstoneFragment<K>] {
+        &self.fragments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fragments.is_empty()
+    }
+
+    /// Returns the fragment containing `key`.
+    pub fn fragment<Q>(&self, key: &Q) -> Option<&RangeTombstoneFragment<K>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let i = self.fragments.partition_point(|f| f.end().borrow() <= key);
+        self.fragments.get(i).filter(|f| f.contains(key))
+    }
+
+    /// Returns `true` if a point value of `key` at `seq` is deleted by a range tombstone, i.e.,
+    /// `seq` is less than the greatest seq of range tombstones containing `key`.
+    pub fn covers<Q>(&self, key: &Q, seq: InternalSeq) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.fragment(key).is_some_and(|f| seq < f.max_seq())
+    }
+
+    /// Returns `true` if a point value of `key` at `seq` is deleted by a range tombstone visible at
+    /// `snapshot`.
+    pub fn covers_at<Q>(&self, key: &Q, seq: InternalSeq, snapshot: InternalSeq) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let max_seq = self.fragment(key).and_then(|f| f.max_seq_at(snapshot));
+        max_seq.is_some_and(|max_seq| seq < max_seq)
+    }
+
+    /// Returns `true` if `value` of `key` is shadowed by a range tombstone.
+    ///
+    /// Consistent with [`RangeTombstone::shadows`], a normal value with the same seq as a range
+    /// tombstone is shadowed.
+    ///
+    /// It can be used to filter the output of a merge:
+    ///
+    /// ```rust
+    /// use seq_marked::FragmentedRangeTombstones;
+    /// use seq_marked::InternalSeq;
+    /// use seq_marked::RangeTombstone;
+    /// use seq_marked::SeqMarked;
+    /// use seq_marked::merge::KMerge;
+    ///
+    /// let rts = FragmentedRangeTombstones::new([RangeTombstone::new(2, 4, InternalSeq::new(5))]);
+    ///
+    /// let a = vec![(1, SeqMarked::new_normal(1, "a")), (3, SeqMarked::new_normal(3, "a"))];
+    /// let b = vec![(2, SeqMarked::new_normal(6, "b"))];
+    ///
+    /// let got = KMerge::new([a.into_iter(), b.into_iter()])
+    ///     .filter(|(k, v)| !rts.shadows(k, v))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(got, vec![(1, SeqMarked::new_normal(1, "a")), (2, SeqMarked::new_normal(6, "b"))]);
+    /// ```
+    pub fn shadows<Q, D>(&self, key: &Q, value: &SeqMarked<D>) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.fragment(key)
+            .is_some_and(|f| SeqMarked::<()>::new_tombstone(*f.max_seq()) > value.order_key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    fn s(seq: u64) -> InternalSeq {
+        InternalSeq::new(seq)
+    }
+
+    fn rt(start: &'static str, end: &'static str, seq: u64) -> RangeTombstone<&'static str> {
+        RangeTombstone::new(start, end, s(seq))
+    }
+
+    fn frag(
+        start: &'static str,
+        end: &'static str,
+        seqs: impl IntoIterator<Item = u64>,
+    ) -> RangeTombstoneFragment<&'static str> {
+        RangeTombstoneFragment::new(start, end, seqs.into_iter().map(s).collect())
+    }
+
+    #[test]
+    fn test_fragment_empty() {
+        let rts = FragmentedRangeTombstones::<&str>::new([]);
+        assert!(rts.is_empty());
+        assert!(!rts.covers("a", s(1)));
+
+        let rts = FragmentedRangeTombstones::new([rt("b", "b", 3), rt("c", "a", 3)]);
+        assert!(rts.is_empty());
+
+        assert!(FragmentedRangeTombstones::<&str>::default().is_empty());
+    }
+
+    #[test]
+    fn test_fragment_overlapping() {
+        let rts = FragmentedRangeTombstones::new([
+            rt("c", "g", 3),
+            rt("a", "e", 5),
+            rt("f", "h", 7),
+            rt("x", "z", 1),
+        ]);
+
+        assert_eq!(rts.fragments(), &[
+            frag("a", "c", [5]),
+            frag("c", "e", [5, 3]),
+            frag("e", "f", [3]),
+            frag("f", "g", [7, 3]),
+            frag("g", "h", [7]),
+            frag("x", "z", [1]),
+        ]);
+    }
+
+    #[test]
+    fn test_fragment_same_range() {
+        let rts =
+            FragmentedRangeTombstones::new([rt("a", "c", 3), rt("a", "c", 5), rt("a", "c", 3)]);
+
+        assert_eq!(rts.fragments(), &[frag("a", "c", [5, 3])]);
+    }
+
+    #[test]
+    fn test_fragment_lookup() {
+        let rts = FragmentedRangeTombstones::new([rt("a", "c", 5), rt("e", "g", 3)]);
+
+        assert_eq!(rts.fragment("0"), None);
+        assert_eq!(rts.fragment("a"), Some(&frag("a", "c", [5])));
+        assert_eq!(rts.fragment("b"), Some(&frag("a", "c", [5])));
+        assert_eq!(rts.fragment("c"), None);
+        assert_eq!(rts.fragment("d"), None);
+        assert_eq!(rts.fragment("f"), Some(&frag("e", "g", [3])));
+        assert_eq!(rts.fragment("g"), None);
+    }
+
+    #[test]
+    fn test_covers() {
+        let rts = FragmentedRangeTombstones::new([rt("a", "e", 5), rt("c", "g", 3)]);
+
+        assert!(rts.covers("b", s(4)));
+        assert!(!rts.covers("b", s(5)));
+        assert!(rts.covers("d", s(4)));
+        assert!(rts.covers("f", s(2)));
+        assert!(!rts.covers("f", s(3)));
+        assert!(!rts.covers("g", s(1)));
+    }
+
+    #[test]
+    fn test_covers_at() {
+        let rts = FragmentedRangeTombstones::new([rt("a", "e", 5), rt("c", "g", 3)]);
+
+        assert!(rts.covers_at("d", s(2), s(10)));
+        assert!(rts.covers_at("d", s(4), s(5)));
+        assert!(!rts.covers_at("d", s(4), s(4)));
+        assert!(!rts.covers_at("d", s(3), s(4)));
+        assert!(!rts.covers_at("d", s(5), s(5)));
+        assert!(rts.covers_at("d", s(2), s(4)));
+        assert!(!rts.covers_at("d", s(2), s(2)));
+        assert!(!rts.covers_at("b", s(2), s(4)));
+    }
+
+    #[test]
+    fn test_shadows() {
+        let rts = FragmentedRangeTombstones::new([rt("a", "e", 5)]);
+
+        assert!(rts.shadows("b", &norm(4, 1u64)));
+        assert!(rts.shadows("b", &norm(5, 1u64)));
+        assert!(!rts.shadows("b", &norm(6, 1u64)));
+        assert!(!rts.shadows("b", &ts::<u64>(5)));
+        assert!(!rts.shadows("e", &norm(4, 1u64)));
+    }
+}
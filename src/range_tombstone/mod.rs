@@ -0,0 +1,149 @@
+//! Range tombstones that delete every key in `[start, end)` at once.
+
+mod fragment;
+mod fragmented;
+
+use core::borrow::Borrow;
+
+pub use fragment::RangeTombstoneFragment;
+pub use fragmented::FragmentedRangeTombstones;
+
+use crate::InternalSeq;
+use crate::SeqMarked;
+
+/// Deletes every key in `[start, end)` written before `seq`.
+///
+/// A range tombstone acts as a [`SeqMarked`] tombstone at `seq` for every key in the range:
+/// it shadows a point value if its [`order_key()`](Self::order_key) is greater than the one of
+/// the value, i.e., a value with a smaller seq, or a normal value with the same seq.
+///
+/// ```rust
+/// use seq_marked::InternalSeq;
+/// use seq_marked::RangeTombstone;
+/// use seq_marked::SeqMarked;
+///
+/// let rt = RangeTombstone::new("b", "d", InternalSeq::new(5));
+///
+/// assert!(rt.shadows("c", &SeqMarked::new_normal(4, "x")));
+/// assert!(!rt.shadows("c", &SeqMarked::new_normal(6, "x")));
+/// assert!(!rt.shadows("d", &SeqMarked::new_normal(4, "x")));
+/// ```
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+pub struct RangeTombstone<K> {
+    start: K,
+    end: K,
+    seq: InternalSeq,
+}
+
+impl<K> RangeTombstone<K> {
+    /// Creates a range tombstone deleting keys in `[start, end)` at `seq`.
+    pub fn new(start: K, end: K, seq: InternalSeq) -> Self {
+        Self { start, end, seq }
+    }
+
+    /// Returns the inclusive start of the range.
+    pub fn start(&self) -> &K {
+        &self.start
+    }
+
+    /// Returns the exclusive end of the range.
+    pub fn end(&self) -> &K {
+        &self.end
+    }
+
+    pub fn seq(&self) -> InternalSeq {
+        self.seq
+    }
+
+    /// Returns ordering key, the same as a point tombstone at the same seq.
+    pub fn order_key(&self) -> SeqMarked<()> {
+        SeqMarked::new_tombstone(*self.seq)
+    }
+
+    pub fn into_parts(self) -> (K, K, InternalSeq) {
+        (self.start, self.end, self.seq)
+    }
+
+    /// Returns `true` if `key` is in `[start, end)`.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.start.borrow() <= key && key < self.end.borrow()
+    }
+
+    /// Returns `true` if a point value of `key` at `seq` is deleted by this range tombstone, i.e.,
+    /// `seq` is less than the seq of this range tombstone.
+    pub fn covers<Q>(&self, key: &Q, seq: InternalSeq) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.contains(key) && seq < self.seq
+    }
+
+    /// Returns `true` if `value` of `key` is shadowed by this range tombstone.
+    pub fn shadows<Q, D>(&self, key: &Q, value: &SeqMarked<D>) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.contains(key) && self.order_key() > value.order_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    #[test]
+    fn test_accessors() {
+        let rt = RangeTombstone::new(1, 5, InternalSeq::new(3));
+        assert_eq!(rt.start(), &1);
+        assert_eq!(rt.end(), &5);
+        assert_eq!(rt.seq(), InternalSeq::new(3));
+        assert_eq!(rt.order_key(), ts(3));
+        assert_eq!(rt.into_parts(), (1, 5, InternalSeq::new(3)));
+    }
+
+    #[test]
+    fn test_contains() {
+        let rt = RangeTombstone::new(1, 5, InternalSeq::new(3));
+        assert!(!rt.contains(&0));
+        assert!(rt.contains(&1));
+        assert!(rt.contains(&4));
+        assert!(!rt.contains(&5));
+
+        let rt = RangeTombstone::new("a".to_string(), "c".to_string(), InternalSeq::new(3));
+        assert!(rt.contains("b"));
+        assert!(!rt.contains("c"));
+    }
+
+    #[test]
+    fn test_covers() {
+        let rt = RangeTombstone::new(1, 5, InternalSeq::new(3));
+        assert!(rt.covers(&1, InternalSeq::new(2)));
+        // A value at the seq of the range tombstone is not covered.
+        assert!(!rt.covers(&1, InternalSeq::new(3)));
+        assert!(!rt.covers(&1, InternalSeq::new(4)));
+        assert!(!rt.covers(&5, InternalSeq::new(2)));
+    }
+
+    #[test]
+    fn test_shadows() {
+        let rt = RangeTombstone::new(1, 5, InternalSeq::new(3));
+        assert!(rt.shadows(&1, &norm(2, "a")));
+        assert!(rt.shadows(&1, &norm(3, "a")));
+        assert!(!rt.shadows(&1, &norm(4, "a")));
+
+        assert!(rt.shadows(&1, &ts::<()>(2)));
+        assert!(!rt.shadows(&1, &ts::<()>(3)));
+
+        assert!(!rt.shadows(&0, &norm(2, "a")));
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use crate::InternalSeq;
+use crate::SeqRange;
+
+/// Thread-safe allocator of monotonically increasing [`InternalSeq`].
+///
+/// It tracks two watermarks:
+/// - `last_allocated`: the greatest seq handed out to a writer;
+/// - `last_published`: the greatest seq up to which every write has been applied, i.e., the seq a
+///   reader should take a snapshot at.
+///
+/// A writer reserves seqs, applies the write, then publishes the reserved range. Ranges may be
+/// published in any order, but `last_published` only advances over a contiguous prefix of
+/// published ranges, so that a reader never observes a half-applied write batch.
+///
+/// Every reserved range must be published, even if the write is abandoned, otherwise
+/// `last_published` stops advancing.
+///
+/// Seq 0 is never allocated, it is the seq of [`SeqMarked::new_not_found()`].
+///
+/// ```rust
+/// use seq_marked::InternalSeq;
+/// use seq_marked::SeqAllocator;
+///
+/// let alloc = SeqAllocator::new(InternalSeq::new(10));
+///
+/// let a = alloc.reserve(3);
+/// let b = alloc.reserve(2);
+/// assert_eq!(a.start(), InternalSeq::new(11));
+/// assert_eq!(b.start(), InternalSeq::new(14));
+///
+/// alloc.publish(b);
+/// assert_eq!(alloc.last_published(), InternalSeq::new(10));
+///
+/// alloc.publish(a);
+/// assert_eq!(alloc.last_published(), InternalSeq::new(15));
+/// ```
+///
+/// [`SeqMarked::new_not_found()`]: crate::SeqMarked::new_not_found
+#[derive(Debug)]
+#[derive(Default)]
+pub struct SeqAllocator {
+    last_allocated: AtomicU64,
+    last_published: AtomicU64,
+
+    /// Applied ranges that can not be published yet because an earlier range is still being
+    /// applied, as `start -> end`.
+    applied: Mutex<BTreeMap<u64, u64>>,
+}
+
+impl SeqAllocator {
+    /// Creates an allocator restored from a persisted high-water mark.
+    ///
+    /// Every seq up to `high_water_mark` is considered allocated and published, and the next
+    /// allocated seq is `high_water_mark + 1`.
+    pub fn new(high_water_mark: InternalSeq) -> Self {
+        Self {
+            last_allocated: AtomicU64::new(*high_water_mark),
+            last_published: AtomicU64::new(*high_water_mark),
+            applied: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Allocates one seq.
+    ///
+    /// # Panics
+    ///
+    /// Panics if seqs are exhausted, as [`reserve()`](Self::reserve) does.
+    pub fn next_seq(&self) -> InternalSeq {
+        self.reserve(1).start()
+    }
+
+    /// Reserves a contiguous range of `n` seqs, e.g., for a write batch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the exclusive end of the range overflows `u64`, i.e., seqs are exhausted. Nothing
+    /// is reserved then, so that a seq is never handed out twice.
+    pub fn reserve(&self, n: u64) -> SeqRange {
+        let res = self.last_allocated.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |prev| {
+            let end = prev.checked_add(1)?.checked_add(n)?;
+            Some(end - 1)
+        });
+
+        let prev = res.unwrap_or_else(|prev| {
+            panic!(
+                "seq overflow: can not reserve {} seqs after seq {}",
+                n, prev
+            )
+        });
+        SeqRange::new(InternalSeq::new(prev + 1), InternalSeq::new(prev + 1 + n))
+    }
+
+    /// Marks a reserved range as applied, and advances `last_published` as far as possible.
+    ///
+    /// A single seq can be published with `publish(seq.into())`.
+    pub fn publish(&self, range: SeqRange) {
+        let Some(last) = range.last() else {
+            return;
+        };
+
+        let mut applied = self.applied.lock().unwrap();
+
+        let mut published = self.last_published.load(Ordering::Relaxed);
+        if *last <= published {
+            return;
+        }
+
+        applied.insert(*range.start(), *range.end());
+
+        while let Some(entry) = applied.first_entry() {
+            if *entry.key() != published + 1 {
+                break;
+            }
+            published = entry.remove() - 1;
+        }
+
+        self.last_published.store(published, Ordering::Release);
+    }
+
+    /// Returns the greatest allocated seq.
+    pub fn last_allocated(&self) -> InternalSeq {
+        InternalSeq::new(self.last_allocated.load(Ordering::Relaxed))
+    }
+
+    /// Returns the greatest seq up to which every write is applied.
+    ///
+    /// This is the seq a reader takes a snapshot at, and the high-water mark to persist.
+    pub fn last_published(&self) -> InternalSeq {
+        InternalSeq::new(self.last_published.load(Ordering::Acquire))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+    use std::panic::AssertUnwindSafe;
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    fn s(seq: u64) -> InternalSeq {
+        InternalSeq::new(seq)
+    }
+
+    #[test]
+    fn test_default() {
+        let alloc = SeqAllocator::default();
+        assert_eq!(alloc.last_allocated(), s(0));
+        assert_eq!(alloc.last_published(), s(0));
+
+        assert_eq!(alloc.next_seq(), s(1));
+        assert_eq!(alloc.next_seq(), s(2));
+        assert_eq!(alloc.last_allocated(), s(2));
+        assert_eq!(alloc.last_published(), s(0));
+    }
+
+    #[test]
+    fn test_restore() {
+        let alloc = SeqAllocator::new(s(100));
+        assert_eq!(alloc.last_allocated(), s(100));
+        assert_eq!(alloc.last_published(), s(100));
+
+        assert_eq!(alloc.next_seq(), s(101));
+    }
+
+    #[test]
+    fn test_reserve() {
+        let alloc = SeqAllocator::new(s(10));
+
+        assert_eq!(alloc.reserve(3), SeqRange::new(s(11), s(14)));
+        assert_eq!(alloc.reserve(0), SeqRange::new(s(14), s(14)));
+        assert_eq!(alloc.reserve(1), SeqRange::new(s(14), s(15)));
+        assert_eq!(alloc.last_allocated(), s(14));
+    }
+
+    #[test]
+    fn test_reserve_near_max() {
+        let alloc = SeqAllocator::new(s(u64::MAX - 3));
+
+        assert_eq!(
+            alloc.reserve(2),
+            SeqRange::new(s(u64::MAX - 2), s(u64::MAX))
+        );
+        assert_eq!(alloc.reserve(0), SeqRange::new(s(u64::MAX), s(u64::MAX)));
+        assert_eq!(alloc.last_allocated(), s(u64::MAX - 1));
+
+        // Exhausted: nothing is reserved, and no seq is handed out again.
+        for n in [1, 2, u64::MAX] {
+            let res = panic::catch_unwind(AssertUnwindSafe(|| alloc.reserve(n)));
+            let msg = res.unwrap_err().downcast::<String>().unwrap();
+            assert!(msg.starts_with("seq overflow: can not reserve"), "{}", msg);
+            assert_eq!(alloc.last_allocated(), s(u64::MAX - 1));
+        }
+
+        alloc.publish(SeqRange::new(s(u64::MAX - 2), s(u64::MAX)));
+        assert_eq!(alloc.last_published(), s(u64::MAX - 1));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "seq overflow: can not reserve 1 seqs after seq 18446744073709551614"
+    )]
+    fn test_next_seq_exhausted() {
+        let alloc = SeqAllocator::new(s(u64::MAX - 1));
+        alloc.next_seq();
+    }
+
+    #[test]
+    fn test_publish_in_order() {
+        let alloc = SeqAllocator::default();
+
+        let a = alloc.reserve(2);
+        let b = alloc.next_seq();
+
+        alloc.publish(a);
+        assert_eq!(alloc.last_published(), s(2));
+
+        alloc.publish(b.into());
+        assert_eq!(alloc.last_published(), s(3));
+    }
+
+    #[test]
+    fn test_publish_out_of_order() {
+        let alloc = SeqAllocator::default();
+
+        let a = alloc.reserve(2);
+        let b = alloc.reserve(3);
+        let c = alloc.reserve(1);
+
+        alloc.publish(c);
+        assert_eq!(alloc.last_published(), s(0));
+
+        alloc.publish(b);
+        assert_eq!(alloc.last_published(), s(0));
+
+        alloc.publish(a);
+        assert_eq!(alloc.last_published(), s(6));
+    }
+
+    #[test]
+    fn test_publish_empty_or_published() {
+        let alloc = SeqAllocator::default();
+
+        let a = alloc.reserve(2);
+        alloc.publish(alloc.reserve(0));
+        assert_eq!(alloc.last_published(), s(0));
+
+        alloc.publish(a);
+        alloc.publish(a);
+        assert_eq!(alloc.last_published(), s(2));
+        assert!(alloc.applied.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_concurrent() {
+        let alloc = Arc::new(SeqAllocator::default());
+
+        let handles = (0..8)
+            .map(|_| {
+                let alloc = alloc.clone();
+                thread::spawn(move || {
+                    for i in 0..100 {
+                        let r = alloc.reserve(i % 3 + 1);
+                        alloc.publish(r);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for h in handles {
+            h.join().unwrap();
+        }
+
+        let total = 8 * (0..100).map(|i| i % 3 + 1).sum::<u64>();
+        assert_eq!(alloc.last_allocated(), s(total));
+        assert_eq!(alloc.last_published(), s(total));
+    }
+}
@@ -0,0 +1,11 @@
+//! Allocates and publishes [`InternalSeq`] for writes.
+//!
+//! [`InternalSeq`]: crate::InternalSeq
+
+#[cfg(feature = "std")]
+mod allocator;
+mod seq_range;
+
+#[cfg(feature = "std")]
+pub use allocator::SeqAllocator;
+pub use seq_range::SeqRange;
@@ -0,0 +1,121 @@
+use core::fmt;
+
+use crate::InternalSeq;
+
+/// A contiguous range of [`InternalSeq`]: `[start, end)`.
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+pub struct SeqRange {
+    start: InternalSeq,
+    end: InternalSeq,
+}
+
+impl SeqRange {
+    /// Creates a range `[start, end)`.
+    pub fn new(start: InternalSeq, end: InternalSeq) -> Self {
+        debug_assert!(start <= end, "start {} > end {}", start, end);
+        Self { start, end }
+    }
+
+    /// Returns the first seq in the range.
+    pub fn start(&self) -> InternalSeq {
+        self.start
+    }
+
+    /// Returns the exclusive end of the range.
+    pub fn end(&self) -> InternalSeq {
+        self.end
+    }
+
+    /// Returns the last seq in the range, or `None` if the range is empty.
+    pub fn last(&self) -> Option<InternalSeq> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(InternalSeq::new(*self.end - 1))
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        *self.end - *self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, seq: InternalSeq) -> bool {
+        self.start <= seq && seq < self.end
+    }
+
+    /// Returns an iterator over every seq in the range.
+    pub fn iter(&self) -> impl Iterator<Item = InternalSeq> + use<> {
+        (*self.start..*self.end).map(InternalSeq::new)
+    }
+}
+
+impl From<InternalSeq> for SeqRange {
+    /// Creates a range containing only `seq`.
+    fn from(seq: InternalSeq) -> Self {
+        Self::new(seq, seq + 1)
+    }
+}
+
+impl fmt::Display for SeqRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {})", *self.start, *self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(seq: u64) -> InternalSeq {
+        InternalSeq::new(seq)
+    }
+
+    #[test]
+    fn test_seq_range() {
+        let r = SeqRange::new(s(3), s(6));
+
+        assert_eq!(r.start(), s(3));
+        assert_eq!(r.end(), s(6));
+        assert_eq!(r.last(), Some(s(5)));
+        assert_eq!(r.len(), 3);
+        assert!(!r.is_empty());
+
+        assert!(!r.contains(s(2)));
+        assert!(r.contains(s(3)));
+        assert!(r.contains(s(5)));
+        assert!(!r.contains(s(6)));
+
+        assert_eq!(r.iter().collect::<Vec<_>>(), vec![s(3), s(4), s(5)]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let r = SeqRange::new(s(3), s(3));
+
+        assert_eq!(r.last(), None);
+        assert_eq!(r.len(), 0);
+        assert!(r.is_empty());
+        assert!(!r.contains(s(3)));
+        assert_eq!(r.iter().count(), 0);
+
+        assert!(SeqRange::default().is_empty());
+    }
+
+    #[test]
+    fn test_from_seq() {
+        let r = SeqRange::from(s(7));
+        assert_eq!(r, SeqRange::new(s(7), s(8)));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(SeqRange::new(s(3), s(6)).to_string(), "[3, 6)");
+    }
+}
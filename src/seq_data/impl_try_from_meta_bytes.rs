@@ -0,0 +1,47 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ConversionError;
+use crate::SeqData;
+
+impl<M> TryFrom<SeqData<(Option<M>, Vec<u8>)>> for SeqData<(Option<M>, String)> {
+    type Error = ConversionError;
+
+    fn try_from(value: SeqData<(Option<M>, Vec<u8>)>) -> Result<Self, Self::Error> {
+        let (seq, (meta, bytes)) = value.into_parts();
+
+        let s = String::from_utf8(bytes).map_err(|e| ConversionError::from(e).with_seq(seq))?;
+
+        Ok(SeqData::new(seq, (meta, s)))
+    }
+}
+
+impl<M> From<SeqData<(Option<M>, String)>> for SeqData<(Option<M>, Vec<u8>)> {
+    fn from(value: SeqData<(Option<M>, String)>) -> Self {
+        value.map(|(meta, s)| (meta, s.into_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_bytes_to_string() {
+        let v = SeqData::new(3, (Some("m"), b"hello".to_vec()));
+        let got: SeqData<(Option<&str>, String)> = v.try_into().unwrap();
+        assert_eq!(got, SeqData::new(3, (Some("m"), "hello".to_string())));
+
+        let back: SeqData<(Option<&str>, Vec<u8>)> = got.into();
+        assert_eq!(back, SeqData::new(3, (Some("m"), b"hello".to_vec())));
+    }
+
+    #[test]
+    fn test_try_from_bytes_to_string_invalid_utf8() {
+        let v = SeqData::new(3, (None::<()>, vec![0xFF]));
+        let err = SeqData::<(Option<()>, String)>::try_from(v).unwrap_err();
+
+        assert_eq!(err.seq(), Some(3));
+        assert_eq!(err.into_bytes(), vec![0xFF]);
+    }
+}
@@ -1,10 +1,12 @@
-use std::fmt;
+use alloc::vec::Vec;
+use core::fmt;
 
 use crate::InternalSeq;
 use crate::SeqMarked;
 
 mod impl_from_seq_marked;
 mod impl_from_seqv;
+mod impl_try_from_meta_bytes;
 
 /// Sequence-numbered non-marked data.
 ///
@@ -132,7 +134,7 @@ impl<D> SeqData<D> {
 
 #[cfg(test)]
 mod tests {
-    use std::cmp::Ordering;
+    use core::cmp::Ordering;
 
     use Ordering::Equal;
     use Ordering::Greater;
@@ -1,15 +1,18 @@
-use std::io;
+use alloc::string::String;
+use alloc::vec::Vec;
 
+use crate::ConversionError;
 use crate::Marked;
 use crate::SeqMarked;
 
 impl<M> TryFrom<SeqMarked<(Option<M>, Vec<u8>)>> for SeqMarked<(Option<M>, String)> {
-    type Error = io::Error;
+    type Error = ConversionError;
 
     fn try_from(value: SeqMarked<(Option<M>, Vec<u8>)>) -> Result<Self, Self::Error> {
         let (seq, marked) = value.into_parts();
 
-        let marked = Marked::<(Option<M>, String)>::try_from(marked)?;
+        let marked =
+            Marked::<(Option<M>, String)>::try_from(marked).map_err(|e| e.with_seq(seq))?;
 
         Ok(SeqMarked::new(seq, marked))
     }
@@ -73,7 +76,8 @@ mod tests {
 
         assert!(result.is_err());
         let error = result.unwrap_err();
-        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.seq(), Some(20));
+        assert_eq!(error.into_bytes(), vec![0xFF, 0xFE, 0xFD]);
     }
 
     #[test]
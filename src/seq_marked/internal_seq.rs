@@ -1,8 +1,8 @@
-use std::fmt;
-use std::ops::Add;
-use std::ops::AddAssign;
-use std::ops::Deref;
-use std::ops::DerefMut;
+use core::fmt;
+use core::ops::Add;
+use core::ops::AddAssign;
+use core::ops::Deref;
+use core::ops::DerefMut;
 
 /// Internal sequence number type.
 ///
@@ -96,8 +96,8 @@ mod tests {
     #[test]
     fn test_transparent_layout() {
         assert_eq!(
-            std::mem::size_of::<InternalSeq>(),
-            std::mem::size_of::<u64>()
+            core::mem::size_of::<InternalSeq>(),
+            core::mem::size_of::<u64>()
         );
     }
 
@@ -6,8 +6,10 @@ mod impl_try_from_meta_bytes;
 mod internal_seq;
 mod order_key;
 mod ref_seq_marked;
+mod sortable_bytes;
 
-use std::fmt;
+use alloc::vec::Vec;
+use core::fmt;
 
 use crate::Marked;
 pub use crate::seq_marked::internal_seq::InternalSeq;
@@ -88,10 +90,13 @@ impl<D> SeqMarked<D> {
         }
     }
 
+    /// Returns `true` if this represents an absent record, e.g., the value returned by
+    /// [`MapApiRO::get()`](crate::MapApiRO::get) for a key that has never been written.
     pub fn is_not_found(&self) -> bool {
         self.is_absent()
     }
 
+    /// Alias of [`is_not_found()`](Self::is_not_found).
     pub fn is_absent(&self) -> bool {
         self.seq == 0 && self.is_tombstone()
     }
@@ -213,7 +218,7 @@ impl<D> SeqMarked<D> {
 
 #[cfg(test)]
 mod tests {
-    use std::cmp::Ordering;
+    use core::cmp::Ordering;
 
     use Ordering::Equal;
     use Ordering::Greater;
@@ -557,3 +562,44 @@ mod tests_serde {
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "proptest")]
+mod tests_proptest {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::strategy;
+
+    proptest! {
+        #[test]
+        fn test_order_key_ordering(a in any::<SeqMarked<u64>>(), b in any::<SeqMarked<u64>>()) {
+            // Ordering by order key is consistent with ordering by value.
+            if a.order_key() < b.order_key() {
+                prop_assert!(a < b);
+            }
+
+            // `max()` returns the one with the greater order key.
+            prop_assert_eq!(
+                SeqMarked::max(a, b).order_key(),
+                core::cmp::max(a.order_key(), b.order_key())
+            );
+        }
+
+        #[test]
+        fn test_user_seq(v in any::<SeqMarked<u64>>()) {
+            if v.is_tombstone() {
+                prop_assert_eq!(v.user_seq(), 0);
+            } else {
+                prop_assert_eq!(v.user_seq(), *v.internal_seq());
+            }
+        }
+
+        #[test]
+        fn test_tombstone_after_normal(v in strategy::seq_marked(any::<u64>(), any::<u64>(), 0.0)) {
+            let t = v.to_tombstone();
+            prop_assert_eq!(t.internal_seq(), v.internal_seq());
+            prop_assert!(t.order_key() > v.order_key());
+        }
+    }
+}
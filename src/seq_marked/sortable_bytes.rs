@@ -0,0 +1,196 @@
+//! Order-preserving binary encoding of the order key `SeqMarked<()>`.
+//!
+//! The encoding is the big-endian `seq` followed by one mark byte, `0` for normal and `1` for
+//! tombstone, so that comparing the bytes gives the same result as comparing the order keys.
+//! The descending encoding inverts every bit, so that the newest version sorts first.
+
+use crate::DecodeError;
+use crate::Marked;
+use crate::seq_marked::SeqMarked;
+
+const NORMAL: u8 = 0;
+const TOMBSTONE: u8 = 1;
+
+impl SeqMarked<()> {
+    /// Length of the sortable encoding of an order key.
+    pub const SORTABLE_BYTES_LEN: usize = 9;
+
+    /// Encodes into bytes whose byte-wise order matches the order of `SeqMarked<()>`.
+    ///
+    /// ```rust
+    /// use seq_marked::SeqMarked;
+    ///
+    /// let a = SeqMarked::new_normal(2, ());
+    /// let b = SeqMarked::new_tombstone(2);
+    /// assert!(a < b);
+    /// assert!(a.to_sortable_bytes() < b.to_sortable_bytes());
+    /// ```
+    pub fn to_sortable_bytes(&self) -> [u8; Self::SORTABLE_BYTES_LEN] {
+        let mut buf = [0u8; Self::SORTABLE_BYTES_LEN];
+        buf[..8].copy_from_slice(&self.seq.to_be_bytes());
+        buf[8] = match self.marked {
+            Marked::Normal(()) => NORMAL,
+            Marked::TombStone => TOMBSTONE,
+        };
+        buf
+    }
+
+    /// Decodes bytes built by [`to_sortable_bytes()`](Self::to_sortable_bytes).
+    pub fn from_sortable_bytes(buf: &[u8]) -> Result<Self, DecodeError> {
+        let buf: [u8; Self::SORTABLE_BYTES_LEN] =
+            buf.try_into().map_err(|_| DecodeError::InvalidLength {
+                expected: Self::SORTABLE_BYTES_LEN,
+                actual: buf.len(),
+            })?;
+
+        let mut seq = [0u8; 8];
+        seq.copy_from_slice(&buf[..8]);
+        let seq = u64::from_be_bytes(seq);
+
+        let marked = match buf[8] {
+            NORMAL => Marked::Normal(()),
+            TOMBSTONE => Marked::TombStone,
+            b => return Err(DecodeError::InvalidMark(b)),
+        };
+
+        Ok(Self::new(seq, marked))
+    }
+
+    /// Encodes into bytes whose byte-wise order is the reverse of the order of `SeqMarked<()>`.
+    ///
+    /// Appended to a user key, newer versions of the key sort first.
+    ///
+    /// ```rust
+    /// use seq_marked::SeqMarked;
+    ///
+    /// let a = SeqMarked::new_normal(2, ());
+    /// let b = SeqMarked::new_normal(3, ());
+    /// assert!(a.to_sortable_bytes_desc() > b.to_sortable_bytes_desc());
+    /// ```
+    pub fn to_sortable_bytes_desc(&self) -> [u8; Self::SORTABLE_BYTES_LEN] {
+        self.to_sortable_bytes().map(|b| !b)
+    }
+
+    /// Decodes bytes built by [`to_sortable_bytes_desc()`](Self::to_sortable_bytes_desc).
+    pub fn from_sortable_bytes_desc(buf: &[u8]) -> Result<Self, DecodeError> {
+        let buf: [u8; Self::SORTABLE_BYTES_LEN] =
+            buf.try_into().map_err(|_| DecodeError::InvalidLength {
+                expected: Self::SORTABLE_BYTES_LEN,
+                actual: buf.len(),
+            })?;
+        Self::from_sortable_bytes(&buf.map(|b| !b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    fn keys() -> Vec<SeqMarked<()>> {
+        vec![
+            SeqMarked::zero(),
+            ts(0),
+            norm(1, ()),
+            ts(1),
+            norm(255, ()),
+            ts(255),
+            norm(256, ()),
+            ts(256),
+            norm(u64::MAX - 1, ()),
+            norm(u64::MAX, ()),
+            SeqMarked::max_value(),
+        ]
+    }
+
+    #[test]
+    fn test_to_sortable_bytes() {
+        assert_eq!(norm(0x0102, ()).to_sortable_bytes(), [
+            0, 0, 0, 0, 0, 0, 1, 2, 0
+        ]);
+        assert_eq!(ts(0x0102).to_sortable_bytes(), [0, 0, 0, 0, 0, 0, 1, 2, 1]);
+        assert_eq!(ts(0x0102).to_sortable_bytes_desc(), [
+            255, 255, 255, 255, 255, 255, 254, 253, 254
+        ]);
+    }
+
+    #[test]
+    fn test_sortable_bytes_round_trip() -> anyhow::Result<()> {
+        for k in keys() {
+            assert_eq!(k, SeqMarked::from_sortable_bytes(&k.to_sortable_bytes())?);
+            assert_eq!(
+                k,
+                SeqMarked::from_sortable_bytes_desc(&k.to_sortable_bytes_desc())?
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sortable_bytes_order() {
+        let keys = keys();
+
+        for a in &keys {
+            for b in &keys {
+                assert_eq!(
+                    a.cmp(b),
+                    a.to_sortable_bytes().cmp(&b.to_sortable_bytes()),
+                    "{:?} vs {:?}",
+                    a,
+                    b
+                );
+                assert_eq!(
+                    b.cmp(a),
+                    a.to_sortable_bytes_desc().cmp(&b.to_sortable_bytes_desc()),
+                    "desc: {:?} vs {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_sortable_bytes_invalid() {
+        let err = SeqMarked::from_sortable_bytes(&[0; 8]).unwrap_err();
+        assert_eq!(err, DecodeError::InvalidLength {
+            expected: 9,
+            actual: 8
+        });
+
+        let err = SeqMarked::from_sortable_bytes(&[0, 0, 0, 0, 0, 0, 0, 1, 2]).unwrap_err();
+        assert_eq!(err, DecodeError::InvalidMark(2));
+
+        let err = SeqMarked::from_sortable_bytes_desc(&[0; 9]).unwrap_err();
+        assert_eq!(err, DecodeError::InvalidMark(0xFF));
+
+        let err = SeqMarked::from_sortable_bytes_desc(&[0; 10]).unwrap_err();
+        assert_eq!(err, DecodeError::InvalidLength {
+            expected: 9,
+            actual: 10
+        });
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "proptest")]
+mod tests_proptest {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_sortable_bytes(a in any::<SeqMarked<()>>(), b in any::<SeqMarked<()>>()) {
+            prop_assert_eq!(SeqMarked::from_sortable_bytes(&a.to_sortable_bytes()).unwrap(), a);
+            prop_assert_eq!(
+                SeqMarked::from_sortable_bytes_desc(&a.to_sortable_bytes_desc()).unwrap(),
+                a
+            );
+
+            prop_assert_eq!(a.cmp(&b), a.to_sortable_bytes().cmp(&b.to_sortable_bytes()));
+            prop_assert_eq!(b.cmp(&a), a.to_sortable_bytes_desc().cmp(&b.to_sortable_bytes_desc()));
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use alloc::vec::Vec;
+
+use crate::Clock;
 use crate::Expirable;
 #[cfg(doc)]
 use crate::SeqMarked;
@@ -49,11 +52,18 @@ pub trait SeqValue<M, V = Vec<u8>> {
     where M: Expirable {
         self.expires_at_ms() < now_ms
     }
+
+    /// Return true if the record is expired at the current time of `clock`.
+    fn is_expired_with(&self, clock: &impl Clock) -> bool
+    where M: Expirable {
+        self.is_expired(clock.now_ms())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ManualClock;
     use crate::testing::*;
 
     #[test]
@@ -89,6 +99,22 @@ mod tests {
         assert!(!sv.is_expired(999));
     }
 
+    #[test]
+    fn test_seq_value_is_expired_with() {
+        let sv = SeqValueImpl {
+            seq: 1,
+            value: Some(200),
+            meta: Some(ExpirableImpl {
+                expires_at_ms: Some(1000),
+            }),
+        };
+
+        let clock = ManualClock::new(1000);
+        assert!(!sv.is_expired_with(&clock));
+        clock.advance(1);
+        assert!(sv.is_expired_with(&clock));
+    }
+
     #[test]
     fn test_seq_value_no_expiration() {
         let sv = SeqValueImpl {
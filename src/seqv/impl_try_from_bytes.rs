@@ -0,0 +1,50 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ConversionError;
+use crate::SeqV;
+
+impl<M> TryFrom<SeqV<M, Vec<u8>>> for SeqV<M, String> {
+    type Error = ConversionError;
+
+    fn try_from(value: SeqV<M, Vec<u8>>) -> Result<Self, Self::Error> {
+        let seq = value.seq;
+        value.try_map(|bytes| {
+            String::from_utf8(bytes).map_err(|e| ConversionError::from(e).with_seq(seq))
+        })
+    }
+}
+
+impl<M> From<SeqV<M, String>> for SeqV<M, Vec<u8>> {
+    fn from(value: SeqV<M, String>) -> Self {
+        value.map(String::into_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_bytes_to_string() {
+        let v = SeqV::new_with_meta(3, Some("m"), b"hello".to_vec());
+        let got: SeqV<&str, String> = v.try_into().unwrap();
+        assert_eq!(got, SeqV::new_with_meta(3, Some("m"), "hello".to_string()));
+
+        let back: SeqV<&str, Vec<u8>> = got.into();
+        assert_eq!(back, SeqV::new_with_meta(3, Some("m"), b"hello".to_vec()));
+    }
+
+    #[test]
+    fn test_try_from_bytes_to_string_invalid_utf8() {
+        let v = SeqV::<(), _>::new(3, vec![b'a', 0xFF]);
+        let err = SeqV::<(), String>::try_from(v).unwrap_err();
+
+        assert_eq!(err.seq(), Some(3));
+        assert_eq!(
+            err.to_string(),
+            "fail to convert Vec<u8> to String at seq 3: invalid utf-8 sequence of 1 bytes from index 1"
+        );
+        assert_eq!(err.into_bytes(), vec![b'a', 0xFF]);
+    }
+}
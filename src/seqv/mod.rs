@@ -1,10 +1,12 @@
 mod impl_from_seq_data;
 mod impl_from_seq_marked;
 mod impl_seq_value;
+mod impl_try_from_bytes;
 
-use std::fmt;
-use std::ops::Deref;
-use std::ops::DerefMut;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+use core::ops::DerefMut;
 
 /// Some value bound with a seq number.
 ///
@@ -0,0 +1,158 @@
+//! Registry of live snapshots.
+
+mod snapshot;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+pub use snapshot::Snapshot;
+
+use crate::CompactionFilter;
+use crate::InternalSeq;
+
+/// Reference counts of live snapshots, keyed by seq.
+pub(crate) type Registry = Arc<Mutex<BTreeMap<InternalSeq, usize>>>;
+
+/// Thread-safe registry of live snapshots.
+///
+/// A snapshot is registered by [`acquire()`](Self::acquire), and is released when the returned
+/// [`Snapshot`] handle and all its clones are dropped.
+///
+/// The live snapshots are the watermarks for compaction and tombstone GC: a version observed by
+/// a live snapshot must be kept.
+///
+/// ```rust
+/// use seq_marked::InternalSeq;
+/// use seq_marked::SnapshotList;
+///
+/// let list = SnapshotList::new();
+///
+/// let s5 = list.acquire(InternalSeq::new(5));
+/// let s3 = list.acquire(InternalSeq::new(3));
+/// assert_eq!(list.oldest(), Some(InternalSeq::new(3)));
+///
+/// drop(s3);
+/// assert_eq!(list.oldest(), Some(InternalSeq::new(5)));
+///
+/// drop(s5);
+/// assert_eq!(list.oldest(), None);
+/// ```
+#[derive(Debug)]
+#[derive(Default)]
+pub struct SnapshotList {
+    registry: Registry,
+}
+
+impl SnapshotList {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a snapshot at `seq`, which stays live until the returned handle is dropped.
+    pub fn acquire(&self, seq: InternalSeq) -> Snapshot {
+        Snapshot::new(self.registry.clone(), seq)
+    }
+
+    /// Returns the oldest live snapshot, or `None` if there is no live snapshot.
+    pub fn oldest(&self) -> Option<InternalSeq> {
+        let registry = self.registry.lock().unwrap();
+        registry.keys().next().copied()
+    }
+
+    /// Returns the live snapshots in ascending order, without duplicates.
+    pub fn snapshots(&self) -> Vec<InternalSeq> {
+        let registry = self.registry.lock().unwrap();
+        registry.keys().copied().collect()
+    }
+
+    /// Returns `true` if there is no live snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.registry.lock().unwrap().is_empty()
+    }
+
+    /// Creates a [`CompactionFilter`] that keeps every version observed by a live snapshot.
+    pub fn compaction_filter(&self, bottommost: bool) -> CompactionFilter {
+        CompactionFilter::new(self.snapshots(), bottommost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    fn s(seq: u64) -> InternalSeq {
+        InternalSeq::new(seq)
+    }
+
+    #[test]
+    fn test_empty() {
+        let list = SnapshotList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.oldest(), None);
+        assert_eq!(list.snapshots(), vec![]);
+    }
+
+    #[test]
+    fn test_acquire_release() {
+        let list = SnapshotList::new();
+
+        let a = list.acquire(s(5));
+        let b = list.acquire(s(3));
+        let c = list.acquire(s(5));
+        let d = list.acquire(s(9));
+
+        assert!(!list.is_empty());
+        assert_eq!(list.oldest(), Some(s(3)));
+        assert_eq!(list.snapshots(), vec![s(3), s(5), s(9)]);
+
+        drop(b);
+        assert_eq!(list.snapshots(), vec![s(5), s(9)]);
+
+        drop(a);
+        assert_eq!(list.snapshots(), vec![s(5), s(9)]);
+
+        drop(c);
+        assert_eq!(list.snapshots(), vec![s(9)]);
+
+        drop(d);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_compaction_filter() {
+        let list = SnapshotList::new();
+        let _a = list.acquire(s(5));
+        let _b = list.acquire(s(3));
+
+        let f = list.compaction_filter(true);
+        assert_eq!(f.snapshots(), &[s(3), s(5)]);
+        assert!(f.is_bottommost());
+    }
+
+    #[test]
+    fn test_concurrent() {
+        let list = SnapshotList::new();
+        let keep = list.acquire(s(1));
+
+        thread::scope(|scope| {
+            for i in 0..8 {
+                let list = &list;
+                scope.spawn(move || {
+                    for j in 0..100 {
+                        let snapshot = list.acquire(s(i * 100 + j + 2));
+                        assert_eq!(list.oldest(), Some(s(1)));
+                        drop(snapshot);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(list.snapshots(), vec![s(1)]);
+        drop(keep);
+        assert!(list.is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::btree_map::Entry;
+use std::fmt;
+
+use crate::InternalSeq;
+use crate::SeqMarked;
+use crate::snapshot_list::Registry;
+
+/// RAII handle of a live snapshot registered in a [`SnapshotList`](crate::SnapshotList).
+///
+/// The snapshot is released when this handle and all its clones are dropped.
+pub struct Snapshot {
+    registry: Registry,
+    seq: InternalSeq,
+}
+
+impl Snapshot {
+    pub(crate) fn new(registry: Registry, seq: InternalSeq) -> Self {
+        *registry.lock().unwrap().entry(seq).or_default() += 1;
+        Self { registry, seq }
+    }
+
+    /// Returns the seq this snapshot is taken at.
+    pub fn seq(&self) -> InternalSeq {
+        self.seq
+    }
+
+    /// Returns `true` if `value` is written at or before this snapshot.
+    pub fn is_visible<D>(&self, value: &SeqMarked<D>) -> bool {
+        value.internal_seq() <= self.seq
+    }
+}
+
+impl Clone for Snapshot {
+    fn clone(&self) -> Self {
+        Self::new(self.registry.clone(), self.seq)
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let mut registry = self.registry.lock().unwrap();
+
+        if let Entry::Occupied(mut e) = registry.entry(self.seq) {
+            *e.get_mut() -= 1;
+            if *e.get() == 0 {
+                e.remove();
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snapshot").field("seq", &self.seq).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InternalSeq;
+    use crate::SnapshotList;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    #[test]
+    fn test_snapshot() {
+        let list = SnapshotList::new();
+        let snapshot = list.acquire(InternalSeq::new(5));
+
+        assert_eq!(snapshot.seq(), InternalSeq::new(5));
+        assert!(snapshot.is_visible(&norm(4, ())));
+        assert!(snapshot.is_visible(&ts::<()>(5)));
+        assert!(!snapshot.is_visible(&norm(6, ())));
+
+        assert_eq!(
+            format!("{:?}", snapshot),
+            "Snapshot { seq: InternalSeq { seq: 5 } }"
+        );
+    }
+
+    #[test]
+    fn test_clone() {
+        let list = SnapshotList::new();
+        let a = list.acquire(InternalSeq::new(5));
+        let b = a.clone();
+
+        drop(a);
+        assert_eq!(list.oldest(), Some(InternalSeq::new(5)));
+
+        drop(b);
+        assert_eq!(list.oldest(), None);
+    }
+}
@@ -0,0 +1,46 @@
+//! Encoding of the index block.
+
+use crate::DecodeError;
+use crate::codec::Reader;
+use crate::codec::put_bytes;
+use crate::sst::BlockHandle;
+
+/// Appends the last key and the handle of a data block to the index block.
+pub(crate) fn put_index_entry(buf: &mut Vec<u8>, last_key: &[u8], handle: BlockHandle) {
+    put_bytes(buf, last_key);
+    handle.encode(buf);
+}
+
+/// Decodes the index block.
+pub(crate) fn decode_index(buf: &[u8]) -> Result<Vec<(Vec<u8>, BlockHandle)>, DecodeError> {
+    let mut r = Reader::new(buf);
+    let mut index = Vec::new();
+
+    while !r.is_empty() {
+        let last_key = r.take_bytes()?;
+        index.push((last_key, BlockHandle::decode(&mut r)?));
+    }
+
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index() {
+        let h1 = BlockHandle { offset: 0, len: 10 };
+        let h2 = BlockHandle { offset: 14, len: 5 };
+
+        let mut buf = vec![];
+        put_index_entry(&mut buf, b"a", h1);
+        put_index_entry(&mut buf, b"bc", h2);
+
+        assert_eq!(
+            decode_index(&buf),
+            Ok(vec![(b"a".to_vec(), h1), (b"bc".to_vec(), h2)])
+        );
+        assert!(decode_index(&buf[..buf.len() - 1]).is_err());
+    }
+}
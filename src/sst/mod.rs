@@ -0,0 +1,124 @@
+//! Sorted string table: an immutable, on-disk sorted run of `(key, SeqMarked<Vec<u8>>)`.
+//!
+//! A table is written once by [`SstWriter`] from entries in the order of [`InternalKey`], and
+//! then read by [`SstReader`] with point lookups, at the latest or at a snapshot, and range scans.
+//! A key may have several versions, e.g., kept for live snapshots when flushing a
+//! [`VersionedMap`]. Tombstones are stored as well, so that a table can shadow older tables below
+//! it.
+//!
+//! A point lookup consults the [`BloomFilter`] of the table first, and does not read a data block
+//! if the key is definitely absent.
+//!
+//! The layout of a table is:
+//!
+//! ```text
+//! | data block | ... | filter block | index block | meta block | footer |
+//! ```
+//!
+//! - Every block is followed by the big-endian CRC32C of the block.
+//! - A data block is a prefix-compressed [`Block`] of entries in the order of [`InternalKey`].
+//! - The filter block is the encoded [`BloomFilter`] of all keys, or empty if disabled.
+//! - The index block contains the last key, the position and the length of every data block.
+//! - The meta block contains the [`SstMeta`].
+//! - The footer is the position and length of the filter block, the index block and the meta block,
+//!   followed by their big-endian CRC32C and the magic bytes [`MAGIC`].
+//!
+//! When opening a table, every block handle is checked to lie before the footer, so that a
+//! corrupted table is reported as an error rather than causing a huge read.
+//!
+//! ```rust
+//! use seq_marked::SeqMarked;
+//! use seq_marked::sst::SstReader;
+//! use seq_marked::sst::SstWriter;
+//!
+//! let mut w = SstWriter::new(Vec::new());
+//! w.add_all([
+//!     ("a", SeqMarked::new_normal(1, "v1")),
+//!     ("b", SeqMarked::new_tombstone(2)),
+//! ])?;
+//! let (buf, meta) = w.finish()?;
+//! assert_eq!(meta.tombstone_count, 1);
+//!
+//! let mut r = SstReader::open(std::io::Cursor::new(buf))?;
+//! assert_eq!(r.get(b"a")?, SeqMarked::new_normal(1, b"v1".to_vec()));
+//! assert_eq!(r.get(b"b")?, SeqMarked::new_tombstone(2));
+//! assert!(r.get(b"c")?.is_not_found());
+//!
+//! let got = r.range(b"b".as_slice()..).collect::<Result<Vec<_>, _>>()?;
+//! assert_eq!(got, vec![(b"b".to_vec(), SeqMarked::new_tombstone(2))]);
+//! # Ok::<(), seq_marked::sst::SstError>(())
+//! ```
+//!
+//! [`Block`]: crate::block::Block
+//! [`InternalKey`]: crate::InternalKey
+//! [`VersionedMap`]: crate::VersionedMap
+//! [`BloomFilter`]: crate::BloomFilter
+
+mod index_block;
+mod reader;
+mod sst_error;
+mod sst_meta;
+mod writer;
+
+pub use reader::SstRange;
+pub use reader::SstReader;
+pub use sst_error::SstError;
+pub use sst_meta::SstMeta;
+pub use writer::SstWriter;
+
+use crate::DecodeError;
+use crate::codec::Reader;
+
+/// The magic bytes at the end of a table.
+pub const MAGIC: [u8; 8] = *b"seqmkSST";
+
+/// The default size of a data block in bytes.
+///
+/// A block is finished once it reaches this size, thus it may be a little larger.
+pub const DEFAULT_BLOCK_SIZE: usize = 4096;
+
+/// Length of the footer: 3 block handles and the magic.
+const FOOTER_LEN: usize = 3 * BlockHandle::ENCODED_LEN + CHECKSUM_LEN + MAGIC.len();
+
+/// Length of the checksum following every block.
+const CHECKSUM_LEN: usize = 4;
+
+/// The position and length of a block, excluding its checksum.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+struct BlockHandle {
+    offset: u64,
+    len: u32,
+}
+
+impl BlockHandle {
+    const ENCODED_LEN: usize = 8 + 4;
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.offset.to_be_bytes());
+        buf.extend_from_slice(&self.len.to_be_bytes());
+    }
+
+    fn decode(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            offset: u64::from_be_bytes(r.take_array()?),
+            len: u32::from_be_bytes(r.take_array()?),
+        })
+    }
+
+    /// Checks that the block and its checksum end at or before `limit`.
+    fn check_within(&self, limit: u64) -> Result<(), SstError> {
+        let end = self.offset.checked_add(self.len as u64 + CHECKSUM_LEN as u64);
+        match end {
+            Some(end) if end <= limit => Ok(()),
+            _ => Err(SstError::Decode {
+                offset: self.offset,
+                source: DecodeError::TooShort {
+                    min: end.and_then(|e| usize::try_from(e).ok()).unwrap_or(usize::MAX),
+                    actual: usize::try_from(limit).unwrap_or(usize::MAX),
+                },
+            }),
+        }
+    }
+}
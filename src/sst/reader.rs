@@ -0,0 +1,667 @@
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::ops::Bound;
+use std::ops::RangeBounds;
+
+use crate::BloomFilter;
+use crate::DecodeError;
+use crate::InternalSeq;
+use crate::SeqMarked;
+use crate::block::Block;
+use crate::block::BlockIter;
+use crate::codec::Reader;
+use crate::sst::BlockHandle;
+use crate::sst::CHECKSUM_LEN;
+use crate::sst::FOOTER_LEN;
+use crate::sst::MAGIC;
+use crate::sst::SstError;
+use crate::sst::SstMeta;
+use crate::sst::index_block::decode_index;
+
+/// Reads a sorted string table written by [`SstWriter`](crate::sst::SstWriter).
+///
+/// The bloom filter, the index and the summary are loaded when opening; a data block is read when
+/// it is needed.
+#[derive(Debug)]
+pub struct SstReader<R> {
+    r: R,
+
+    meta: SstMeta,
+
+    /// The bloom filter of all keys, or `None` if the table is built without it.
+    filter: Option<BloomFilter>,
+
+    /// The last key and the handle of every data block.
+    index: Vec<(Vec<u8>, BlockHandle)>,
+}
+
+impl<R> SstReader<R>
+where R: Read + Seek
+{
+    /// Opens a table, loading its bloom filter, index and summary.
+    pub fn open(mut r: R) -> Result<Self, SstError> {
+        let len = r.seek(SeekFrom::End(0))?;
+        if len < FOOTER_LEN as u64 {
+            return Err(SstError::Decode {
+                offset: 0,
+                source: DecodeError::TooShort {
+                    min: FOOTER_LEN,
+                    actual: len as usize,
+                },
+            });
+        }
+
+        let footer_offset = len - FOOTER_LEN as u64;
+        let mut footer = [0u8; FOOTER_LEN];
+        r.seek(SeekFrom::Start(footer_offset))?;
+        r.read_exact(&mut footer)?;
+
+        let mut fr = Reader::new(&footer);
+        let decode_err = |source| SstError::Decode {
+            offset: footer_offset,
+            source,
+        };
+        let handles = fr.take(3 * BlockHandle::ENCODED_LEN).map_err(decode_err)?;
+        let checksum: [u8; CHECKSUM_LEN] = fr.take_array().map_err(decode_err)?;
+        let magic: [u8; 8] = fr.take_array().map_err(decode_err)?;
+        if magic != MAGIC {
+            return Err(SstError::InvalidMagic { actual: magic });
+        }
+
+        let expected = u32::from_be_bytes(checksum);
+        let actual = crc32c::crc32c(handles);
+        if actual != expected {
+            return Err(SstError::Checksum {
+                offset: footer_offset,
+                expected,
+                actual,
+            });
+        }
+
+        let mut hr = Reader::new(handles);
+        let filter_handle = BlockHandle::decode(&mut hr).map_err(decode_err)?;
+        let index_handle = BlockHandle::decode(&mut hr).map_err(decode_err)?;
+        let meta_handle = BlockHandle::decode(&mut hr).map_err(decode_err)?;
+        for handle in [filter_handle, index_handle, meta_handle] {
+            handle.check_within(footer_offset)?;
+        }
+
+        let meta = read_block(&mut r, meta_handle)?;
+        let meta = SstMeta::decode(&meta).map_err(|source| SstError::Decode {
+            offset: meta_handle.offset,
+            source,
+        })?;
+
+        let index = read_block(&mut r, index_handle)?;
+        let index = decode_index(&index).map_err(|source| SstError::Decode {
+            offset: index_handle.offset,
+            source,
+        })?;
+
+        // Data blocks are followed by the filter block.
+        for (_, handle) in &index {
+            handle.check_within(filter_handle.offset)?;
+        }
+
+        let filter = read_block(&mut r, filter_handle)?;
+        let filter = if filter.is_empty() {
+            None
+        } else {
+            let f = BloomFilter::decode(&filter).map_err(|source| SstError::Decode {
+                offset: filter_handle.offset,
+                source,
+            })?;
+            Some(f)
+        };
+
+        Ok(Self {
+            r,
+            meta,
+            filter,
+            index,
+        })
+    }
+
+    /// Returns the summary of the table.
+    pub fn meta(&self) -> &SstMeta {
+        &self.meta
+    }
+
+    /// Returns `false` if `key` is definitely not in the table, without reading a data block.
+    ///
+    /// It always returns `true` if the table is built without a bloom filter.
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        self.filter.as_ref().is_none_or(|f| f.may_contain(key))
+    }
+
+    /// Consumes self and returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+
+    /// Returns the newest version of `key`.
+    ///
+    /// A deleted key returns a tombstone; an absent key returns [`SeqMarked::new_not_found()`].
+    pub fn get(&mut self, key: &[u8]) -> Result<SeqMarked, SstError> {
+        self.get_at(key, InternalSeq::new(u64::MAX))
+    }
+
+    /// Returns the newest version of `key` whose seq is `<= snapshot`.
+    ///
+    /// A tombstone is returned as is; [`SeqMarked::new_not_found()`] means no version is visible
+    /// at `snapshot`.
+    pub fn get_at(&mut self, key: &[u8], snapshot: InternalSeq) -> Result<SeqMarked, SstError> {
+        if !self.may_contain(key) {
+            return Ok(SeqMarked::new_not_found());
+        }
+
+        // Versions are stored newest first, and may span several data blocks.
+        for res in self.range::<[u8], _>((Bound::Included(key), Bound::Included(key))) {
+            let (_, v) = res?;
+            if v.internal_seq() <= snapshot {
+                return Ok(v);
+            }
+        }
+
+        Ok(SeqMarked::new_not_found())
+    }
+
+    /// Returns every version of keys in `range`, sorted by key then newest first, including
+    /// tombstones.
+    pub fn range<K, B>(&mut self, range: B) -> SstRange<'_, R>
+    where
+        K: AsRef<[u8]> + ?Sized,
+        B: RangeBounds<K>,
+    {
+        let start = range.start_bound().map(|k| k.as_ref().to_vec());
+        let end = range.end_bound().map(|k| k.as_ref().to_vec());
+
+        let next_block = match &start {
+            Bound::Included(k) | Bound::Excluded(k) => {
+                self.index.partition_point(|(last, _)| last < k)
+            }
+            Bound::Unbounded => 0,
+        };
+
+        SstRange {
+            reader: self,
+            start,
+            end,
+            next_block,
+            block: None,
+            done: false,
+        }
+    }
+
+    /// Reads the `i`-th data block, and returns its position and an iterator over it.
+    fn read_data_block(&mut self, i: usize) -> Result<(u64, BlockIter<Vec<u8>>), SstError> {
+        let handle = self.index[i].1;
+        let buf = read_block(&mut self.r, handle)?;
+        let block = Block::new(buf).map_err(|source| SstError::Decode {
+            offset: handle.offset,
+            source,
+        })?;
+        Ok((handle.offset, block.into_iter()))
+    }
+}
+
+/// An iterator over the entries of a table in a key range, returned by [`SstReader::range()`].
+///
+/// The iteration ends after the first error.
+#[derive(Debug)]
+pub struct SstRange<'a, R> {
+    reader: &'a mut SstReader<R>,
+    start: Bound<Vec<u8>>,
+    end: Bound<Vec<u8>>,
+
+    /// The index of the next data block to read.
+    next_block: usize,
+
+    /// The position of the current data block and an iterator over it.
+    block: Option<(u64, BlockIter<Vec<u8>>)>,
+
+    done: bool,
+}
+
+impl<R> SstRange<'_, R>
+where R: Read + Seek
+{
+    /// Reads the next data block; a block may start before `start` thus it seeks to `start`.
+    fn load_block(&mut self) -> Result<(), SstError> {
+        let (offset, mut it) = self.reader.read_data_block(self.next_block)?;
+
+        if let Bound::Included(k) | Bound::Excluded(k) = &self.start {
+            it.seek(k).map_err(|source| SstError::Decode { offset, source })?;
+        }
+
+        self.block = Some((offset, it));
+        Ok(())
+    }
+}
+
+impl<R> Iterator for SstRange<'_, R>
+where R: Read + Seek
+{
+    type Item = Result<(Vec<u8>, SeqMarked), SstError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            if let Some((offset, it)) = &mut self.block {
+                let (key, value) = match it.next() {
+                    Some(Ok(entry)) => entry,
+                    Some(Err(source)) => {
+                        let offset = *offset;
+                        self.done = true;
+                        return Some(Err(SstError::Decode { offset, source }));
+                    }
+                    None => {
+                        self.block = None;
+                        continue;
+                    }
+                };
+
+                let after_start = match &self.start {
+                    Bound::Included(s) => &key >= s,
+                    Bound::Excluded(s) => &key > s,
+                    Bound::Unbounded => true,
+                };
+                if !after_start {
+                    continue;
+                }
+
+                let before_end = match &self.end {
+                    Bound::Included(e) => &key <= e,
+                    Bound::Excluded(e) => &key < e,
+                    Bound::Unbounded => true,
+                };
+                if !before_end {
+                    self.done = true;
+                    return None;
+                }
+
+                return Some(Ok((key, value)));
+            }
+
+            if self.next_block >= self.reader.index.len() {
+                self.done = true;
+                return None;
+            }
+
+            match self.load_block() {
+                Ok(()) => self.next_block += 1,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Reads a block and verifies its checksum.
+fn read_block<R>(r: &mut R, handle: BlockHandle) -> Result<Vec<u8>, SstError>
+where R: Read + Seek {
+    let mut buf = vec![0u8; handle.len as usize + CHECKSUM_LEN];
+    r.seek(SeekFrom::Start(handle.offset))?;
+    r.read_exact(&mut buf)?;
+
+    let checksum = buf.split_off(handle.len as usize);
+    let expected = u32::from_be_bytes(checksum.try_into().unwrap());
+    let actual = crc32c::crc32c(&buf);
+    if actual != expected {
+        return Err(SstError::Checksum {
+            offset: handle.offset,
+            expected,
+            actual,
+        });
+    }
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::VersionedMap;
+    use crate::sst::SstWriter;
+    use crate::sst::index_block::put_index_entry;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    fn key(i: u64) -> Vec<u8> {
+        format!("key-{:04}", i).into_bytes()
+    }
+
+    /// Build entries of keys `0, 2, 4, ..., 198`, every third one is a tombstone.
+    fn sample() -> Vec<(Vec<u8>, SeqMarked)> {
+        (0..100)
+            .map(|i| {
+                let v = if i % 3 == 0 {
+                    SeqMarked::new_tombstone(i + 1)
+                } else {
+                    SeqMarked::new_normal(i + 1, format!("v{}", i).into_bytes())
+                };
+                (key(i * 2), v)
+            })
+            .collect()
+    }
+
+    fn open(entries: &[(Vec<u8>, SeqMarked)], block_size: usize) -> SstReader<Cursor<Vec<u8>>> {
+        let mut w = SstWriter::new(Vec::new()).with_block_size(block_size);
+        w.add_all(entries.iter().map(|(k, v)| (k, v.as_ref()))).unwrap();
+        let (buf, _) = w.finish().unwrap();
+        SstReader::open(Cursor::new(buf)).unwrap()
+    }
+
+    #[test]
+    fn test_get() -> anyhow::Result<()> {
+        for block_size in [1, 64, 4096] {
+            let entries = sample();
+            let mut r = open(&entries, block_size);
+
+            assert_eq!(r.meta().entry_count, 100);
+            assert_eq!(r.meta().tombstone_count, 34);
+
+            for (k, v) in &entries {
+                assert_eq!(&r.get(k)?, v);
+            }
+
+            assert_eq!(r.get(&key(2))?, norm(2, b"v1".to_vec()));
+            assert_eq!(r.get(&key(6))?, ts(4));
+
+            for i in [1, 7, 199, 1000] {
+                assert!(r.get(&key(i))?.is_not_found());
+            }
+            assert!(r.get(b"")?.is_not_found());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_range() -> anyhow::Result<()> {
+        for block_size in [1, 64, 4096] {
+            let entries = sample();
+            let mut r = open(&entries, block_size);
+
+            let got = r.range::<[u8], _>(..).collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(got, entries);
+
+            let got = r.range(key(3)..key(9)).collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(got, entries[2..5].to_vec());
+
+            let got = r.range(key(4)..=key(8)).collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(got, entries[2..5].to_vec());
+
+            let got = r
+                .range((Bound::Excluded(key(4)), Bound::Unbounded))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(got, entries[3..].to_vec());
+
+            let got = r.range(key(500)..).collect::<Result<Vec<_>, _>>()?;
+            assert!(got.is_empty());
+        }
+        Ok(())
+    }
+
+    /// Flushes a [`VersionedMap`] with several versions per key, and reads it at every snapshot.
+    #[test]
+    fn test_versions() -> anyhow::Result<()> {
+        let mut m = VersionedMap::new();
+        for seq in 1..=60u64 {
+            let k = key(seq % 7);
+            if seq % 5 == 0 {
+                m.delete(k, InternalSeq::new(seq));
+            } else {
+                m.insert(k, InternalSeq::new(seq), format!("v{}", seq).into_bytes());
+            }
+        }
+
+        let entries = m.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>();
+
+        for block_size in [1, 64, 4096] {
+            let mut r = open(&entries, block_size);
+
+            let got = r.range::<[u8], _>(..).collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(got, entries);
+
+            for i in 0..8 {
+                let k = key(i);
+
+                let newest =
+                    m.versions(&k).next().cloned().unwrap_or_else(SeqMarked::new_not_found);
+                assert_eq!(r.get(&k)?, newest);
+
+                for snapshot in 0..=61 {
+                    let snapshot = InternalSeq::new(snapshot);
+                    let want =
+                        m.get_at(&k, snapshot).cloned().unwrap_or_else(SeqMarked::new_not_found);
+                    assert_eq!(
+                        r.get_at(&k, snapshot)?,
+                        want,
+                        "key: {}, snapshot: {}",
+                        i,
+                        snapshot
+                    );
+                }
+
+                let got = r.range(k.clone()..=k.clone()).collect::<Result<Vec<_>, _>>()?;
+                let want = m.versions(&k).map(|v| (k.clone(), v.clone())).collect::<Vec<_>>();
+                assert_eq!(got, want);
+            }
+
+            // An excluded start skips every version of the key.
+            let got = r
+                .range((Bound::Excluded(key(5)), Bound::Unbounded))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert!(got.iter().all(|(k, _)| k == &key(6)));
+            assert_eq!(got.len(), m.versions(&key(6)).count());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bloom_filter() -> anyhow::Result<()> {
+        let entries = sample();
+
+        // Corrupt every data block, so that a lookup touching one fails.
+        let mut w = SstWriter::new(Vec::new()).with_block_size(1);
+        w.add_all(entries.iter().map(|(k, v)| (k, v.as_ref())))?;
+        let (mut buf, _) = w.finish()?;
+        let r = SstReader::open(Cursor::new(buf.clone()))?;
+        for (_, h) in &r.index {
+            buf[h.offset as usize] ^= 0xff;
+        }
+        let mut r = SstReader::open(Cursor::new(buf))?;
+
+        // Present keys, including tombstone-only ones, may be present.
+        for (k, _) in &entries {
+            assert!(r.may_contain(k));
+            assert!(matches!(r.get(k), Err(SstError::Checksum { .. })));
+        }
+
+        // Absent keys are mostly answered without reading a data block.
+        let absent = (0..1000).map(|i| key(i * 2 + 1));
+        let skipped = absent.filter(|k| !r.may_contain(k)).count();
+        assert!(skipped > 950, "skipped: {}", skipped);
+
+        let k = (0..).map(|i| key(i * 2 + 1)).find(|k| !r.may_contain(k)).unwrap();
+        assert!(r.get(&k)?.is_not_found());
+        Ok(())
+    }
+
+    #[test]
+    fn test_without_bloom_filter() -> anyhow::Result<()> {
+        let entries = sample();
+        let mut w = SstWriter::new(Vec::new()).with_bloom_filter(None);
+        w.add_all(entries.iter().map(|(k, v)| (k, v.as_ref())))?;
+        let (buf, _) = w.finish()?;
+
+        let mut r = SstReader::open(Cursor::new(buf))?;
+        assert!(r.filter.is_none());
+        assert!(r.may_contain(&key(1)));
+        assert!(r.get(&key(1))?.is_not_found());
+        assert_eq!(r.get(&key(2))?, entries[1].1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty() -> anyhow::Result<()> {
+        let mut r = open(&[], 64);
+
+        assert_eq!(r.meta(), &SstMeta::default());
+        assert!(r.get(b"a")?.is_not_found());
+        assert_eq!(r.range::<[u8], _>(..).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() -> anyhow::Result<()> {
+        let (buf, _) = SstWriter::new(Vec::new()).finish()?;
+
+        let err = SstReader::open(Cursor::new(&buf[..FOOTER_LEN - 1])).unwrap_err();
+        assert!(matches!(err, SstError::Decode {
+            source: DecodeError::TooShort { .. },
+            ..
+        }));
+
+        let mut bad = buf.clone();
+        *bad.last_mut().unwrap() ^= 0xff;
+        let err = SstReader::open(Cursor::new(bad)).unwrap_err();
+        assert!(matches!(err, SstError::InvalidMagic { .. }));
+
+        // A corrupted footer.
+        let footer_offset = buf.len() - FOOTER_LEN;
+        let mut bad = buf.clone();
+        bad[footer_offset] ^= 0xff;
+        let err = SstReader::open(Cursor::new(bad)).unwrap_err();
+        assert!(matches!(err, SstError::Checksum { offset, .. } if offset == footer_offset as u64));
+        Ok(())
+    }
+
+    /// Replaces the footer of `buf` with one of `handles`, with a valid checksum.
+    fn with_footer(buf: &[u8], handles: [BlockHandle; 3]) -> Vec<u8> {
+        let mut buf = buf[..buf.len() - FOOTER_LEN].to_vec();
+        let mut footer = Vec::new();
+        for h in handles {
+            h.encode(&mut footer);
+        }
+        let checksum = crc32c::crc32c(&footer);
+        footer.extend_from_slice(&checksum.to_be_bytes());
+        footer.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&footer);
+        buf
+    }
+
+    #[test]
+    fn test_handle_out_of_bounds() -> anyhow::Result<()> {
+        let entries = sample();
+        let mut w = SstWriter::new(Vec::new()).with_block_size(64);
+        w.add_all(entries.iter().map(|(k, v)| (k, v.as_ref())))?;
+        let (buf, _) = w.finish()?;
+
+        let footer_offset = (buf.len() - FOOTER_LEN) as u64;
+        let mut fr = Reader::new(&buf[footer_offset as usize..]);
+        let filter = BlockHandle::decode(&mut fr)?;
+        let index = BlockHandle::decode(&mut fr)?;
+        let meta = BlockHandle::decode(&mut fr)?;
+
+        // The original footer rewritten is valid.
+        SstReader::open(Cursor::new(with_footer(&buf, [filter, index, meta])))?;
+
+        let overlapping_footer = BlockHandle {
+            offset: meta.offset,
+            len: meta.len + 1,
+        };
+        let overflowing = BlockHandle {
+            offset: u64::MAX - 2,
+            len: u32::MAX,
+        };
+
+        for bad in [overlapping_footer, overflowing] {
+            let footers = [[bad, index, meta], [filter, bad, meta], [
+                filter, index, bad,
+            ]];
+            for handles in footers {
+                let err = SstReader::open(Cursor::new(with_footer(&buf, handles))).unwrap_err();
+                assert!(
+                    matches!(err, SstError::Decode { offset, source: DecodeError::TooShort { .. } } if offset == bad.offset),
+                    "{:?}",
+                    err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_handle_out_of_bounds() -> anyhow::Result<()> {
+        let entries = sample();
+        let mut w = SstWriter::new(Vec::new()).with_block_size(64);
+        w.add_all(entries.iter().map(|(k, v)| (k, v.as_ref())))?;
+        let (buf, _) = w.finish()?;
+
+        let footer_offset = (buf.len() - FOOTER_LEN) as u64;
+        let mut fr = Reader::new(&buf[footer_offset as usize..]);
+        let filter = BlockHandle::decode(&mut fr)?;
+        let index = BlockHandle::decode(&mut fr)?;
+        let meta = BlockHandle::decode(&mut fr)?;
+
+        // Append an index whose only data block overlaps the filter block.
+        let mut buf = buf[..footer_offset as usize].to_vec();
+        let mut bad_index = Vec::new();
+        let bad = BlockHandle {
+            offset: 0,
+            len: filter.offset as u32,
+        };
+        put_index_entry(&mut bad_index, &key(0), bad);
+        let bad_index_handle = BlockHandle {
+            offset: buf.len() as u64,
+            len: bad_index.len() as u32,
+        };
+        buf.extend_from_slice(&bad_index);
+        buf.extend_from_slice(&crc32c::crc32c(&bad_index).to_be_bytes());
+        buf.extend_from_slice(&[0; FOOTER_LEN]);
+
+        let err = SstReader::open(Cursor::new(with_footer(&buf, [
+            filter,
+            bad_index_handle,
+            meta,
+        ])))
+        .unwrap_err();
+        assert!(matches!(err, SstError::Decode {
+            offset: 0,
+            source: DecodeError::TooShort { .. }
+        }));
+
+        // The original index is valid.
+        SstReader::open(Cursor::new(with_footer(&buf, [filter, index, meta])))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupted_block() -> anyhow::Result<()> {
+        let entries = sample();
+        let mut w = SstWriter::new(Vec::new()).with_block_size(64);
+        w.add_all(entries.iter().map(|(k, v)| (k, v.as_ref())))?;
+        let (mut buf, _) = w.finish()?;
+
+        // Corrupt the first data block.
+        buf[0] ^= 0xff;
+        let mut r = SstReader::open(Cursor::new(buf))?;
+
+        let err = r.get(&key(0)).unwrap_err();
+        assert!(matches!(err, SstError::Checksum { offset: 0, .. }));
+
+        // The range scan stops at the error.
+        let mut it = r.range::<[u8], _>(..);
+        assert!(matches!(it.next(), Some(Err(SstError::Checksum { .. }))));
+        assert!(it.next().is_none());
+
+        assert_eq!(r.get(&key(198))?, entries[99].1);
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::DecodeError;
+use crate::InternalKey;
+
+/// Error writing or reading a sorted string table.
+///
+/// `offset` is the position of the bad block in the table.
+#[derive(Debug)]
+pub enum SstError {
+    Io(io::Error),
+
+    /// An entry added to a writer is not after the previous one in the order of [`InternalKey`].
+    Unsorted {
+        prev: InternalKey,
+        key: InternalKey,
+    },
+
+    /// A block to write exceeds `u32::MAX` bytes, the largest a block handle can address.
+    BlockTooLarge {
+        len: usize,
+    },
+
+    /// The input does not end with the magic bytes, i.e., it is not a table.
+    InvalidMagic {
+        actual: [u8; 8],
+    },
+
+    /// The checksum of a block does not match its content.
+    Checksum {
+        offset: u64,
+        expected: u32,
+        actual: u32,
+    },
+
+    /// A block is malformed.
+    Decode {
+        offset: u64,
+        source: DecodeError,
+    },
+}
+
+impl From<io::Error> for SstError {
+    fn from(e: io::Error) -> Self {
+        SstError::Io(e)
+    }
+}
+
+impl fmt::Display for SstError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SstError::Io(e) => write!(f, "SST I/O error: {}", e),
+            SstError::Unsorted { prev, key } => write!(
+                f,
+                "SST entries must be in InternalKey order: {:?} after {:?}",
+                key, prev
+            ),
+            SstError::BlockTooLarge { len } => {
+                write!(f, "SST block of {} bytes exceeds u32::MAX", len)
+            }
+            SstError::InvalidMagic { actual } => {
+                write!(f, "SST invalid magic: {:?}", actual)
+            }
+            SstError::Checksum {
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "SST checksum mismatch at offset {}: expected {:#010x}, actual {:#010x}",
+                offset, expected, actual
+            ),
+            SstError::Decode { offset, source } => {
+                write!(f, "SST invalid block at offset {}: {}", offset, source)
+            }
+        }
+    }
+}
+
+impl Error for SstError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SstError::Io(e) => Some(e),
+            SstError::Decode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InternalSeq;
+
+    #[test]
+    fn test_display() {
+        let e = SstError::Unsorted {
+            prev: InternalKey::new_normal(b"b".to_vec(), InternalSeq::new(1)),
+            key: InternalKey::new_normal(b"a".to_vec(), InternalSeq::new(2)),
+        };
+        assert!(
+            e.to_string().starts_with(
+                "SST entries must be in InternalKey order: InternalKey { user_key: [97]"
+            )
+        );
+
+        let e = SstError::BlockTooLarge { len: 1 << 32 };
+        assert_eq!(
+            e.to_string(),
+            "SST block of 4294967296 bytes exceeds u32::MAX"
+        );
+
+        let e = SstError::Decode {
+            offset: 3,
+            source: DecodeError::InvalidMark(2),
+        };
+        assert_eq!(
+            e.to_string(),
+            "SST invalid block at offset 3: invalid mark byte: 2"
+        );
+        assert!(e.source().is_some());
+    }
+}
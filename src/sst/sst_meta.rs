@@ -0,0 +1,91 @@
+use crate::DecodeError;
+use crate::InternalSeq;
+use crate::codec::Reader;
+use crate::codec::put_bytes;
+
+/// Summary of the entries in a table, stored in the table.
+///
+/// For an empty table, every field is the default value.
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct SstMeta {
+    /// The number of entries, including tombstones.
+    pub entry_count: u64,
+
+    /// The number of tombstones.
+    pub tombstone_count: u64,
+
+    /// The smallest seq of all entries.
+    pub min_seq: InternalSeq,
+
+    /// The greatest seq of all entries.
+    pub max_seq: InternalSeq,
+
+    /// The first key.
+    pub min_key: Vec<u8>,
+
+    /// The last key.
+    pub max_key: Vec<u8>,
+}
+
+impl SstMeta {
+    pub(crate) fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.entry_count.to_be_bytes());
+        buf.extend_from_slice(&self.tombstone_count.to_be_bytes());
+        buf.extend_from_slice(&self.min_seq.to_be_bytes());
+        buf.extend_from_slice(&self.max_seq.to_be_bytes());
+        put_bytes(buf, &self.min_key);
+        put_bytes(buf, &self.max_key);
+    }
+
+    pub(crate) fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader::new(buf);
+
+        let meta = Self {
+            entry_count: u64::from_be_bytes(r.take_array()?),
+            tombstone_count: u64::from_be_bytes(r.take_array()?),
+            min_seq: InternalSeq::new(u64::from_be_bytes(r.take_array()?)),
+            max_seq: InternalSeq::new(u64::from_be_bytes(r.take_array()?)),
+            min_key: r.take_bytes()?,
+            max_key: r.take_bytes()?,
+        };
+
+        if !r.is_empty() {
+            return Err(DecodeError::InvalidLength {
+                expected: buf.len() - r.remaining().len(),
+                actual: buf.len(),
+            });
+        }
+
+        Ok(meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let meta = SstMeta {
+            entry_count: 3,
+            tombstone_count: 1,
+            min_seq: InternalSeq::new(2),
+            max_seq: InternalSeq::new(9),
+            min_key: b"a".to_vec(),
+            max_key: b"xyz".to_vec(),
+        };
+
+        let mut buf = vec![];
+        meta.encode(&mut buf);
+        assert_eq!(SstMeta::decode(&buf), Ok(meta));
+
+        buf.push(0);
+        assert!(matches!(
+            SstMeta::decode(&buf),
+            Err(DecodeError::InvalidLength { .. })
+        ));
+    }
+}
@@ -0,0 +1,295 @@
+use std::io::Write;
+
+use crate::BloomFilterBuilder;
+use crate::InternalKey;
+use crate::SeqMarked;
+use crate::block::BlockBuilder;
+use crate::sst::BlockHandle;
+use crate::sst::CHECKSUM_LEN;
+use crate::sst::DEFAULT_BLOCK_SIZE;
+use crate::sst::MAGIC;
+use crate::sst::SstError;
+use crate::sst::SstMeta;
+use crate::sst::index_block::put_index_entry;
+
+/// Writes a sorted string table from entries in the order of [`InternalKey`]: sorted by key,
+/// then by order key descending.
+///
+/// A key may have several versions, e.g., kept for live snapshots, which are added newest first.
+/// The table is complete only after [`finish()`](Self::finish).
+#[derive(Debug)]
+pub struct SstWriter<W> {
+    w: W,
+
+    block_size: usize,
+
+    /// The number of bytes written.
+    offset: u64,
+
+    /// The data block being built.
+    block: BlockBuilder,
+
+    /// The index block being built.
+    index: Vec<u8>,
+
+    /// The internal key of the last entry added.
+    last: Option<InternalKey>,
+
+    /// Builds the bloom filter of all keys, or `None` if disabled.
+    filter: Option<BloomFilterBuilder>,
+
+    meta: SstMeta,
+}
+
+impl<W> SstWriter<W>
+where W: Write
+{
+    /// Creates a writer writing a table to `w`.
+    pub fn new(w: W) -> Self {
+        Self {
+            w,
+            block_size: DEFAULT_BLOCK_SIZE,
+            offset: 0,
+            block: BlockBuilder::new(),
+            index: Vec::new(),
+            last: None,
+            filter: Some(BloomFilterBuilder::default()),
+            meta: SstMeta::default(),
+        }
+    }
+
+    /// Sets the size in bytes at which a data block is finished.
+    #[must_use]
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    /// Sets the bits per key of the bloom filter, or disables it with `None`.
+    ///
+    /// By default, a filter with [`BloomFilterBuilder::DEFAULT_BITS_PER_KEY`] is built.
+    #[must_use]
+    pub fn with_bloom_filter(mut self, bits_per_key: Option<usize>) -> Self {
+        self.filter = bits_per_key.map(BloomFilterBuilder::new);
+        self
+    }
+
+    /// Adds an entry, which must be after the previous one in the order of [`InternalKey`].
+    ///
+    /// An entry too large to fit in a block of `u32::MAX` bytes is rejected with
+    /// [`SstError::BlockTooLarge`].
+    pub fn add<K, D>(&mut self, key: K, value: SeqMarked<D>) -> Result<(), SstError>
+    where
+        K: AsRef<[u8]>,
+        D: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let internal_key = InternalKey::from_seq_marked(key.to_vec(), &value);
+
+        let is_new_key = match &self.last {
+            Some(prev) if &internal_key <= prev => {
+                return Err(SstError::Unsorted {
+                    prev: prev.clone(),
+                    key: internal_key,
+                });
+            }
+            Some(prev) => prev.user_key().as_slice() != key,
+            None => true,
+        };
+
+        // Check before changing any state, so that a rejected entry leaves the writer usable.
+        let value = value.as_ref().map(|d| d.as_ref());
+        let entry_len = BlockBuilder::max_entry_len(key, &value);
+        if self.block.estimated_len() + entry_len > MAX_BLOCK_LEN {
+            self.flush_block()?;
+        }
+        check_block_len(self.block.estimated_len() + entry_len)?;
+
+        let seq = value.internal_seq();
+        if self.meta.entry_count == 0 {
+            self.meta.min_key = key.to_vec();
+            self.meta.min_seq = seq;
+            self.meta.max_seq = seq;
+        } else {
+            self.meta.min_seq = self.meta.min_seq.min(seq);
+            self.meta.max_seq = self.meta.max_seq.max(seq);
+        }
+        self.meta.entry_count += 1;
+        if value.is_tombstone() {
+            self.meta.tombstone_count += 1;
+        }
+
+        // A tombstone is added too, because it must be found to shadow lower levels.
+        if let Some(filter) = &mut self.filter {
+            if is_new_key {
+                filter.add(key);
+            }
+        }
+
+        self.block.add(key, value);
+        self.last = Some(internal_key);
+
+        if self.block.estimated_len() >= self.block_size {
+            self.flush_block()?;
+        }
+        Ok(())
+    }
+
+    /// Adds entries in the order of [`InternalKey`].
+    pub fn add_all<K, D>(
+        &mut self,
+        entries: impl IntoIterator<Item = (K, SeqMarked<D>)>,
+    ) -> Result<(), SstError>
+    where
+        K: AsRef<[u8]>,
+        D: AsRef<[u8]>,
+    {
+        for (key, value) in entries {
+            self.add(key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the rest of the table, and returns the underlying writer and the table summary.
+    pub fn finish(mut self) -> Result<(W, SstMeta), SstError> {
+        self.flush_block()?;
+
+        if let Some(last) = self.last.take() {
+            self.meta.max_key = last.into_parts().0;
+        }
+
+        let filter = self.filter.take().map(|f| f.build().encode()).unwrap_or_default();
+        let filter_handle = self.write_block(&filter)?;
+
+        let index = std::mem::take(&mut self.index);
+        let index_handle = self.write_block(&index)?;
+
+        let mut meta = Vec::new();
+        self.meta.encode(&mut meta);
+        let meta_handle = self.write_block(&meta)?;
+
+        let mut footer = Vec::new();
+        filter_handle.encode(&mut footer);
+        index_handle.encode(&mut footer);
+        meta_handle.encode(&mut footer);
+        let checksum = crc32c::crc32c(&footer);
+        footer.extend_from_slice(&checksum.to_be_bytes());
+        footer.extend_from_slice(&MAGIC);
+        self.w.write_all(&footer)?;
+        self.w.flush()?;
+
+        Ok((self.w, self.meta))
+    }
+
+    /// Writes the data block being built, and adds it to the index.
+    fn flush_block(&mut self) -> Result<(), SstError> {
+        if self.block.is_empty() {
+            return Ok(());
+        }
+
+        let block = std::mem::take(&mut self.block).finish();
+        let handle = self.write_block(&block)?;
+
+        let last_key = self.last.as_ref().map(|k| k.user_key().as_slice()).unwrap_or_default();
+        put_index_entry(&mut self.index, last_key, handle);
+        Ok(())
+    }
+
+    /// Writes a block followed by its checksum.
+    fn write_block(&mut self, block: &[u8]) -> Result<BlockHandle, SstError> {
+        let handle = BlockHandle {
+            offset: self.offset,
+            len: check_block_len(block.len())?,
+        };
+
+        self.w.write_all(block)?;
+        self.w.write_all(&crc32c::crc32c(block).to_be_bytes())?;
+        self.offset += (block.len() + CHECKSUM_LEN) as u64;
+
+        Ok(handle)
+    }
+}
+
+/// The largest block a [`BlockHandle`] can address.
+const MAX_BLOCK_LEN: usize = u32::MAX as usize;
+
+/// Returns the length of a block as `u32`, or an error if it is too large to address.
+fn check_block_len(len: usize) -> Result<u32, SstError> {
+    u32::try_from(len).map_err(|_| SstError::BlockTooLarge { len })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InternalSeq;
+
+    #[test]
+    fn test_meta() -> anyhow::Result<()> {
+        let mut w = SstWriter::new(Vec::new());
+        w.add("a", SeqMarked::new_normal(5, "x"))?;
+        w.add("b", SeqMarked::<&str>::new_tombstone(9))?;
+        w.add("c", SeqMarked::new_normal(2, "y"))?;
+        let (_, meta) = w.finish()?;
+
+        assert_eq!(meta, SstMeta {
+            entry_count: 3,
+            tombstone_count: 1,
+            min_seq: InternalSeq::new(2),
+            max_seq: InternalSeq::new(9),
+            min_key: b"a".to_vec(),
+            max_key: b"c".to_vec(),
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty() -> anyhow::Result<()> {
+        let (buf, meta) = SstWriter::new(Vec::new()).finish()?;
+
+        assert_eq!(meta, SstMeta::default());
+        assert!(buf.ends_with(&MAGIC));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bloom_filter_size() -> anyhow::Result<()> {
+        let entries = (0..100u64).map(|i| (i.to_be_bytes(), SeqMarked::new_normal(i, "")));
+
+        let mut w = SstWriter::new(Vec::new()).with_bloom_filter(None);
+        w.add_all(entries.clone())?;
+        let (without, _) = w.finish()?;
+
+        let mut w = SstWriter::new(Vec::new()).with_bloom_filter(Some(16));
+        w.add_all(entries)?;
+        let (with, _) = w.finish()?;
+
+        // 100 keys * 16 bits, and the number of probes.
+        assert_eq!(with.len() - without.len(), 200 + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsorted() -> anyhow::Result<()> {
+        let mut w = SstWriter::new(Vec::new());
+        w.add("b", SeqMarked::new_normal(2, "x"))?;
+
+        // A smaller key, a newer version, or the same version.
+        for (key, seq) in [("a", 1), ("b", 3), ("b", 2)] {
+            let err = w.add(key, SeqMarked::new_normal(seq, "y")).unwrap_err();
+            assert!(matches!(err, SstError::Unsorted { prev, .. } if prev.user_key() == b"b"));
+        }
+
+        // An older version of the same key.
+        w.add("b", SeqMarked::new_normal(1, "z"))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_block_len() {
+        assert_eq!(check_block_len(0).unwrap(), 0);
+        assert_eq!(check_block_len(MAX_BLOCK_LEN).unwrap(), u32::MAX);
+
+        let err = check_block_len(MAX_BLOCK_LEN + 1).unwrap_err();
+        assert!(matches!(err, SstError::BlockTooLarge { len } if len == MAX_BLOCK_LEN + 1));
+    }
+}
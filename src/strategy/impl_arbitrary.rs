@@ -0,0 +1,115 @@
+use std::fmt;
+
+use proptest::prelude::*;
+
+use crate::InternalSeq;
+use crate::Marked;
+use crate::SeqData;
+use crate::SeqMarked;
+use crate::SeqV;
+use crate::strategy::DEFAULT_TOMBSTONE_RATIO;
+
+impl Arbitrary for InternalSeq {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        crate::strategy::internal_seq(any::<u64>()).boxed()
+    }
+}
+
+impl<D> Arbitrary for Marked<D>
+where D: Arbitrary + fmt::Debug + 'static
+{
+    type Parameters = D::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        crate::strategy::marked(any_with::<D>(args), DEFAULT_TOMBSTONE_RATIO).boxed()
+    }
+}
+
+impl<D> Arbitrary for SeqMarked<D>
+where D: Arbitrary + fmt::Debug + 'static
+{
+    type Parameters = D::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        crate::strategy::seq_marked(any::<u64>(), any_with::<D>(args), DEFAULT_TOMBSTONE_RATIO)
+            .boxed()
+    }
+}
+
+impl<D> Arbitrary for SeqData<D>
+where D: Arbitrary + fmt::Debug + 'static
+{
+    type Parameters = D::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        crate::strategy::seq_data(any::<u64>(), any_with::<D>(args)).boxed()
+    }
+}
+
+impl<M, T> Arbitrary for SeqV<M, T>
+where
+    M: Arbitrary + fmt::Debug + 'static,
+    T: Arbitrary + fmt::Debug + 'static,
+{
+    type Parameters = (M::Parameters, T::Parameters);
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        let (meta_args, data_args) = args;
+        let meta = any_with::<Option<M>>((Default::default(), meta_args));
+        crate::strategy::seqv(any::<u64>(), meta, any_with::<T>(data_args)).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::strategy::ValueTree;
+    use proptest::test_runner::TestRunner;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_arbitrary(
+            s in any::<InternalSeq>(),
+            m in any::<Marked<u64>>(),
+            sm in any::<SeqMarked<Vec<u8>>>(),
+            sd in any::<SeqData<String>>(),
+            v in any::<SeqV<u64, Vec<u8>>>(),
+        ) {
+            prop_assert_eq!(SeqMarked::new(*s, m).into_parts(), (*s, m));
+
+            if sm.is_tombstone() {
+                prop_assert_eq!(sm.user_seq(), 0);
+                prop_assert_eq!(sm.data_ref(), None);
+            } else {
+                prop_assert_eq!(sm.user_seq(), *sm.internal_seq());
+            }
+
+            let back: Option<SeqData<String>> = SeqMarked::from(sd.clone()).into();
+            prop_assert_eq!(back, Some(sd));
+
+            let back: Option<SeqV<u64, Vec<u8>>> = SeqMarked::from(v.clone()).into();
+            prop_assert_eq!(back, Some(v));
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_marks() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = any::<SeqMarked<u64>>();
+
+        let values =
+            (0..200).map(|_| strategy.new_tree(&mut runner).unwrap().current()).collect::<Vec<_>>();
+
+        let tombstones = values.iter().filter(|v| v.is_tombstone()).count();
+        assert!(tombstones > 0, "no tombstone generated");
+        assert!(tombstones < values.len(), "no normal value generated");
+    }
+}
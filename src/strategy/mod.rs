@@ -0,0 +1,130 @@
+//! Strategies of [`mod@proptest`] for the core types.
+//!
+//! Functions in this module build strategies with controllable seq distribution and tombstone
+//! ratio. The core types also implement [`Arbitrary`], using arbitrary seqs and
+//! [`DEFAULT_TOMBSTONE_RATIO`].
+//!
+//! ```rust
+//! use proptest::prelude::*;
+//! use seq_marked::strategy;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn test_tombstone_seq(v in strategy::seq_marked(1..100u64, any::<u64>(), 1.0)) {
+//!         prop_assert!(v.is_tombstone());
+//!         prop_assert_eq!(v.user_seq(), 0);
+//!     }
+//! }
+//! ```
+
+mod impl_arbitrary;
+mod version_chain;
+
+use std::fmt;
+
+use proptest::prelude::*;
+pub use version_chain::version_chain;
+pub use version_chain::version_chains;
+
+use crate::InternalSeq;
+use crate::Marked;
+use crate::SeqData;
+use crate::SeqMarked;
+use crate::SeqV;
+
+/// The probability of generating a tombstone by the `Arbitrary` impls.
+pub const DEFAULT_TOMBSTONE_RATIO: f64 = 0.2;
+
+/// Generates [`InternalSeq`] with seq from `seq`.
+pub fn internal_seq(seq: impl Strategy<Value = u64>) -> impl Strategy<Value = InternalSeq> {
+    seq.prop_map(InternalSeq::new)
+}
+
+/// Generates [`Marked`] that is a tombstone with probability `tombstone_ratio`, in `[0.0, 1.0]`.
+///
+/// It shrinks towards normal values.
+pub fn marked<D>(
+    data: impl Strategy<Value = D>,
+    tombstone_ratio: f64,
+) -> impl Strategy<Value = Marked<D>>
+where
+    D: fmt::Debug,
+{
+    (proptest::bool::weighted(tombstone_ratio), data).prop_map(|(is_tombstone, d)| {
+        if is_tombstone {
+            Marked::TombStone
+        } else {
+            Marked::Normal(d)
+        }
+    })
+}
+
+/// Generates [`SeqMarked`] with seq from `seq`, that is a tombstone with probability
+/// `tombstone_ratio`.
+pub fn seq_marked<D>(
+    seq: impl Strategy<Value = u64>,
+    data: impl Strategy<Value = D>,
+    tombstone_ratio: f64,
+) -> impl Strategy<Value = SeqMarked<D>>
+where
+    D: fmt::Debug,
+{
+    (seq, marked(data, tombstone_ratio)).prop_map(|(seq, m)| SeqMarked::new(seq, m))
+}
+
+/// Generates [`SeqData`] with seq from `seq`.
+pub fn seq_data<D>(
+    seq: impl Strategy<Value = u64>,
+    data: impl Strategy<Value = D>,
+) -> impl Strategy<Value = SeqData<D>>
+where
+    D: fmt::Debug,
+{
+    (seq, data).prop_map(|(seq, d)| SeqData::new(seq, d))
+}
+
+/// Generates [`SeqV`] with seq from `seq`.
+pub fn seqv<M, T>(
+    seq: impl Strategy<Value = u64>,
+    meta: impl Strategy<Value = Option<M>>,
+    data: impl Strategy<Value = T>,
+) -> impl Strategy<Value = SeqV<M, T>>
+where
+    M: fmt::Debug,
+    T: fmt::Debug,
+{
+    (seq, meta, data).prop_map(|(seq, m, d)| SeqV::new_with_meta(seq, m, d))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::collection::vec;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_internal_seq(s in internal_seq(5..10u64)) {
+            prop_assert!((5..10).contains(&*s));
+        }
+
+        #[test]
+        fn test_tombstone_ratio(
+            normals in vec(marked(any::<u8>(), 0.0), 10),
+            tombstones in vec(marked(any::<u8>(), 1.0), 10),
+        ) {
+            prop_assert!(normals.iter().all(|m| matches!(m, Marked::Normal(_))));
+            prop_assert!(tombstones.iter().all(|m| matches!(m, Marked::TombStone)));
+        }
+
+        #[test]
+        fn test_seq_data_and_seqv(
+            d in seq_data(1..3u64, Just("a")),
+            v in seqv(1..3u64, Just(Some("m")), Just("a")),
+        ) {
+            prop_assert!((1..3).contains(&d.user_seq()));
+            prop_assert!((1..3).contains(&v.seq));
+            prop_assert_eq!(v.meta, Some("m"));
+        }
+    }
+}
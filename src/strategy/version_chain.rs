@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use proptest::collection::SizeRange;
+use proptest::collection::btree_map;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::SeqMarked;
+use crate::strategy::marked;
+
+/// Generates the versions of one key, with distinct seqs from `seq`, sorted by seq ascending.
+///
+/// The number of versions is in `len`; the lower bound may not be reached if `seq` does not
+/// provide enough distinct values.
+pub fn version_chain<D>(
+    seq: impl Strategy<Value = u64>,
+    data: impl Strategy<Value = D>,
+    tombstone_ratio: f64,
+    len: impl Into<SizeRange>,
+) -> impl Strategy<Value = Vec<SeqMarked<D>>>
+where
+    D: fmt::Debug,
+{
+    btree_map(seq, marked(data, tombstone_ratio), len)
+        .prop_map(|m| m.into_iter().map(|(seq, m)| SeqMarked::new(seq, m)).collect())
+}
+
+/// Generates a history of writes grouped by key, as version chains sorted by seq ascending.
+///
+/// The number of writes is in `writes`. The i-th write is assigned seq `i + 1`, thus seqs are
+/// unique across all keys, as in a store with a global seq allocator.
+pub fn version_chains<K, D>(
+    key: impl Strategy<Value = K>,
+    data: impl Strategy<Value = D>,
+    tombstone_ratio: f64,
+    writes: impl Into<SizeRange>,
+) -> impl Strategy<Value = BTreeMap<K, Vec<SeqMarked<D>>>>
+where
+    K: Ord + fmt::Debug,
+    D: fmt::Debug,
+{
+    vec((key, marked(data, tombstone_ratio)), writes).prop_map(|writes| {
+        let mut chains: BTreeMap<K, Vec<SeqMarked<D>>> = BTreeMap::new();
+
+        for (i, (k, m)) in writes.into_iter().enumerate() {
+            chains.entry(k).or_default().push(SeqMarked::new(i as u64 + 1, m));
+        }
+        chains
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_version_chain(chain in version_chain(0..100u64, any::<u8>(), 0.5, 0..20)) {
+            prop_assert!(chain.len() < 20);
+            prop_assert!(chain.windows(2).all(|w| w[0].order_key() < w[1].order_key()));
+            prop_assert!(chain.windows(2).all(|w| w[0].internal_seq() < w[1].internal_seq()));
+        }
+
+        #[test]
+        fn test_version_chains(chains in version_chains(0..5u8, any::<u8>(), 0.5, 0..50)) {
+            let mut seqs = vec![];
+            for chain in chains.values() {
+                prop_assert!(!chain.is_empty());
+                prop_assert!(chain.windows(2).all(|w| w[0].internal_seq() < w[1].internal_seq()));
+                seqs.extend(chain.iter().map(|v| *v.internal_seq()));
+            }
+
+            seqs.sort();
+            let expected = (1..=seqs.len() as u64).collect::<Vec<_>>();
+            prop_assert_eq!(seqs, expected);
+        }
+    }
+}
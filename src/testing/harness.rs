@@ -0,0 +1,555 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Bound;
+
+use crate::InternalSeq;
+use crate::MapApi;
+use crate::MapApiSnapshot;
+use crate::SeqMarked;
+use crate::testing::Oracle;
+
+/// An operation to replay against a [`MapApi`] implementation.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub enum Op<K, D> {
+    /// Writes a value, or a tombstone if `None`.
+    Set(K, Option<D>),
+
+    /// Reads the value of a key.
+    Get(K),
+
+    /// Reads the values of keys in a range, including tombstones.
+    ///
+    /// The range must be valid for [`BTreeMap::range()`](alloc::collections::BTreeMap::range),
+    /// i.e., start is not greater than end, and they are not equal and both excluded.
+    Range(Bound<K>, Bound<K>),
+
+    /// Reads the value of a key visible at a snapshot.
+    ///
+    /// Only supported by [`replay_snapshot()`].
+    GetAt(K, InternalSeq),
+
+    /// Reads the values of keys in a range visible at a snapshot, including tombstones.
+    ///
+    /// The range must be valid as in [`Op::Range`]. Only supported by [`replay_snapshot()`].
+    RangeAt(Bound<K>, Bound<K>, InternalSeq),
+}
+
+/// The output of an [`Op`].
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub enum Output<K, D> {
+    /// The values before and after a write.
+    Set(SeqMarked<D>, SeqMarked<D>),
+
+    /// The output of [`Op::Get`] or [`Op::GetAt`].
+    Get(SeqMarked<D>),
+
+    /// The output of [`Op::Range`] or [`Op::RangeAt`].
+    Range(Vec<(K, SeqMarked<D>)>),
+}
+
+/// The first operation whose output differs from the oracle.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct Divergence<K, D> {
+    /// The index of the operation in the log.
+    pub index: usize,
+    pub op: Op<K, D>,
+
+    /// The output of the oracle.
+    pub expected: Output<K, D>,
+    pub actual: Output<K, D>,
+}
+
+impl<K, D> fmt::Display for Divergence<K, D>
+where
+    K: fmt::Debug,
+    D: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "op #{} {:?} diverged: expected {:?}, actual {:?}",
+            self.index, self.op, self.expected, self.actual
+        )
+    }
+}
+
+impl<K, D> core::error::Error for Divergence<K, D>
+where
+    K: fmt::Debug,
+    D: fmt::Debug,
+{
+}
+
+/// Replays `ops` against both `oracle` and `map`, and returns the first divergence.
+///
+/// `map` must assign seqs the same way as the oracle: a write uses the seq of the last write plus
+/// one. Start both from the same state, e.g., with the same last seq.
+///
+/// ```rust
+/// use core::ops::Bound;
+///
+/// use seq_marked::BTreeMapApi;
+/// use seq_marked::testing::Op;
+/// use seq_marked::testing::Oracle;
+/// use seq_marked::testing::replay;
+///
+/// let ops = vec![
+///     Op::Set(1, Some("a")),
+///     Op::Set(2, None),
+///     Op::Get(1),
+///     Op::Range(Bound::Unbounded, Bound::Unbounded),
+/// ];
+///
+/// let mut oracle = Oracle::default();
+/// let mut map = BTreeMapApi::default();
+/// replay(ops, &mut oracle, &mut map).unwrap();
+/// ```
+///
+/// # Panics
+///
+/// Panics on [`Op::GetAt`] or [`Op::RangeAt`]; use [`replay_snapshot()`] for a map that reads at
+/// a snapshot.
+pub fn replay<K, D, M>(
+    ops: impl IntoIterator<Item = Op<K, D>>,
+    oracle: &mut Oracle<K, D>,
+    map: &mut M,
+) -> Result<(), Divergence<K, D>>
+where
+    K: Ord + Clone,
+    D: Clone + PartialEq,
+    M: MapApi<K, Data = D>,
+{
+    replay_by(ops, oracle, map, apply, apply)
+}
+
+/// Replays `ops`, including reads at a snapshot, against both `oracle` and `map`, and returns the
+/// first divergence.
+///
+/// `map` must assign seqs the same way as the oracle, as in [`replay()`].
+pub fn replay_snapshot<K, D, M>(
+    ops: impl IntoIterator<Item = Op<K, D>>,
+    oracle: &mut Oracle<K, D>,
+    map: &mut M,
+) -> Result<(), Divergence<K, D>>
+where
+    K: Ord + Clone,
+    D: Clone + PartialEq,
+    M: MapApi<K, Data = D> + MapApiSnapshot<K>,
+{
+    replay_by(ops, oracle, map, apply_snapshot, apply_snapshot)
+}
+
+/// Applies an [`Op`] to a map and returns its output.
+type ApplyFn<K, D, M> = fn(&Op<K, D>, &mut M) -> Output<K, D>;
+
+fn replay_by<K, D, M>(
+    ops: impl IntoIterator<Item = Op<K, D>>,
+    oracle: &mut Oracle<K, D>,
+    map: &mut M,
+    apply_oracle: ApplyFn<K, D, Oracle<K, D>>,
+    apply_map: ApplyFn<K, D, M>,
+) -> Result<(), Divergence<K, D>>
+where
+    K: PartialEq,
+    D: PartialEq,
+{
+    for (index, op) in ops.into_iter().enumerate() {
+        let expected = apply_oracle(&op, oracle);
+        let actual = apply_map(&op, map);
+
+        if expected != actual {
+            return Err(Divergence {
+                index,
+                op,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn apply<K, D, M>(op: &Op<K, D>, map: &mut M) -> Output<K, D>
+where
+    K: Clone,
+    D: Clone,
+    M: MapApi<K, Data = D>,
+{
+    match op {
+        Op::Set(k, d) => {
+            let (prev, result) = map.set(k.clone(), d.clone());
+            Output::Set(prev, result)
+        }
+        Op::Get(k) => Output::Get(map.get(k)),
+        Op::Range(start, end) => Output::Range(map.range((start.clone(), end.clone())).collect()),
+        Op::GetAt(..) | Op::RangeAt(..) => {
+            panic!("reads at a snapshot must be replayed by replay_snapshot()")
+        }
+    }
+}
+
+fn apply_snapshot<K, D, M>(op: &Op<K, D>, map: &mut M) -> Output<K, D>
+where
+    K: Clone,
+    D: Clone,
+    M: MapApi<K, Data = D> + MapApiSnapshot<K>,
+{
+    match op {
+        Op::GetAt(k, snapshot) => Output::Get(map.get_at(k, *snapshot)),
+        Op::RangeAt(start, end, snapshot) => {
+            Output::Range(map.range_at((start.clone(), end.clone()), *snapshot).collect())
+        }
+        _ => apply(op, map),
+    }
+}
+
+/// Generates an operation log of `len` operations on keys from `key` and data from `data`.
+///
+/// About half of the operations are writes, one in five of which is a delete. Generated ranges
+/// are always valid.
+#[cfg(feature = "proptest")]
+pub fn ops<K, D>(
+    key: impl proptest::strategy::Strategy<Value = K> + Clone,
+    data: impl proptest::strategy::Strategy<Value = D>,
+    len: impl Into<proptest::collection::SizeRange>,
+) -> impl proptest::strategy::Strategy<Value = Vec<Op<K, D>>>
+where
+    K: Ord + Clone + fmt::Debug,
+    D: Clone + fmt::Debug,
+{
+    use proptest::prelude::*;
+
+    let op = prop_oneof![
+        4 => (key.clone(), proptest::option::weighted(0.8, data)).prop_map(|(k, d)| Op::Set(k, d)),
+        3 => key.clone().prop_map(Op::Get),
+        1 => bounds(key).prop_map(|(start, end)| Op::Range(start, end)),
+    ];
+
+    proptest::collection::vec(op, len)
+}
+
+/// Generates an operation log like [`ops()`], in which about half of the reads are at a snapshot
+/// from `snapshot`, to replay by [`replay_snapshot()`].
+#[cfg(feature = "proptest")]
+pub fn snapshot_ops<K, D>(
+    key: impl proptest::strategy::Strategy<Value = K> + Clone,
+    data: impl proptest::strategy::Strategy<Value = D>,
+    snapshot: impl proptest::strategy::Strategy<Value = InternalSeq> + Clone,
+    len: impl Into<proptest::collection::SizeRange>,
+) -> impl proptest::strategy::Strategy<Value = Vec<Op<K, D>>>
+where
+    K: Ord + Clone + fmt::Debug,
+    D: Clone + fmt::Debug,
+{
+    use proptest::prelude::*;
+
+    let op = prop_oneof![
+        8 => (key.clone(), proptest::option::weighted(0.8, data)).prop_map(|(k, d)| Op::Set(k, d)),
+        3 => key.clone().prop_map(Op::Get),
+        3 => (key.clone(), snapshot.clone()).prop_map(|(k, s)| Op::GetAt(k, s)),
+        1 => bounds(key.clone()).prop_map(|(start, end)| Op::Range(start, end)),
+        1 => (bounds(key), snapshot).prop_map(|((start, end), s)| Op::RangeAt(start, end, s)),
+    ];
+
+    proptest::collection::vec(op, len)
+}
+
+/// Generates valid bounds of a range on keys from `key`.
+#[cfg(feature = "proptest")]
+fn bounds<K>(
+    key: impl proptest::strategy::Strategy<Value = K> + Clone,
+) -> impl proptest::strategy::Strategy<Value = (Bound<K>, Bound<K>)>
+where K: Ord + Clone + fmt::Debug {
+    use proptest::prelude::*;
+
+    (key.clone(), key, 0..3u8, 0..3u8)
+        .prop_filter("empty range with equal excluded bounds", |(a, b, s, e)| {
+            a != b || (*s, *e) != (2, 2)
+        })
+        .prop_map(|(a, b, s, e)| {
+            let (a, b) = if a <= b { (a, b) } else { (b, a) };
+            let bound = |kind, k| match kind {
+                0 => Bound::Unbounded,
+                1 => Bound::Included(k),
+                _ => Bound::Excluded(k),
+            };
+            (bound(s, a), bound(e, b))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BTreeMapApi;
+    use crate::LeveledView;
+    use crate::MapApiRO;
+    use crate::VersionedMap;
+    use crate::testing::norm;
+
+    /// A buggy map that rewrites the previous data instead of deleting it.
+    #[derive(Default)]
+    struct NoDelete(BTreeMapApi<u64, u64>);
+
+    impl MapApiRO<u64> for NoDelete {
+        type Data = u64;
+
+        fn get(&self, key: &u64) -> SeqMarked<u64> {
+            self.0.get(key)
+        }
+
+        fn range<R>(&self, range: R) -> impl Iterator<Item = (u64, SeqMarked<u64>)>
+        where R: core::ops::RangeBounds<u64> {
+            self.0.range(range)
+        }
+    }
+
+    impl MapApi<u64> for NoDelete {
+        fn set(&mut self, key: u64, data: Option<u64>) -> (SeqMarked<u64>, SeqMarked<u64>) {
+            match data {
+                Some(d) => self.0.set(key, Some(d)),
+                None => {
+                    let (prev, _) = self.0.set(key, prev_data(&self.0, key));
+                    (prev, SeqMarked::new_tombstone(*self.0.last_seq()))
+                }
+            }
+        }
+    }
+
+    fn prev_data(m: &BTreeMapApi<u64, u64>, key: u64) -> Option<u64> {
+        m.get(&key).into_data()
+    }
+
+    /// A map keeping every version, to read at a snapshot.
+    #[derive(Default)]
+    pub(super) struct Versioned {
+        last_seq: InternalSeq,
+        map: VersionedMap<u64, u64>,
+    }
+
+    impl MapApiRO<u64> for Versioned {
+        type Data = u64;
+
+        fn get(&self, key: &u64) -> SeqMarked<u64> {
+            self.get_at(key, self.last_seq)
+        }
+
+        fn range<R>(&self, range: R) -> impl Iterator<Item = (u64, SeqMarked<u64>)>
+        where R: core::ops::RangeBounds<u64> {
+            self.range_at(range, self.last_seq)
+        }
+    }
+
+    impl MapApiSnapshot<u64> for Versioned {
+        fn get_at(&self, key: &u64, snapshot: InternalSeq) -> SeqMarked<u64> {
+            self.map.get_at(key, snapshot).cloned().unwrap_or_else(SeqMarked::new_not_found)
+        }
+
+        fn range_at<R>(
+            &self,
+            range: R,
+            snapshot: InternalSeq,
+        ) -> impl Iterator<Item = (u64, SeqMarked<u64>)>
+        where
+            R: core::ops::RangeBounds<u64>,
+        {
+            self.map.range_at(range, snapshot).map(|(k, v)| (*k, *v))
+        }
+    }
+
+    impl MapApi<u64> for Versioned {
+        fn set(&mut self, key: u64, data: Option<u64>) -> (SeqMarked<u64>, SeqMarked<u64>) {
+            let prev = self.get(&key);
+
+            self.last_seq += 1;
+            match data {
+                Some(d) => self.map.insert(key, self.last_seq, d),
+                None => self.map.delete(key, self.last_seq),
+            }
+            (prev, self.get(&key))
+        }
+    }
+
+    /// A buggy map that ignores the snapshot and reads the latest values.
+    #[derive(Default)]
+    struct Latest(BTreeMapApi<u64, u64>);
+
+    impl MapApiRO<u64> for Latest {
+        type Data = u64;
+
+        fn get(&self, key: &u64) -> SeqMarked<u64> {
+            self.0.get(key)
+        }
+
+        fn range<R>(&self, range: R) -> impl Iterator<Item = (u64, SeqMarked<u64>)>
+        where R: core::ops::RangeBounds<u64> {
+            self.0.range(range)
+        }
+    }
+
+    impl MapApiSnapshot<u64> for Latest {
+        fn get_at(&self, key: &u64, _snapshot: InternalSeq) -> SeqMarked<u64> {
+            self.0.get(key)
+        }
+
+        fn range_at<R>(
+            &self,
+            range: R,
+            _snapshot: InternalSeq,
+        ) -> impl Iterator<Item = (u64, SeqMarked<u64>)>
+        where
+            R: core::ops::RangeBounds<u64>,
+        {
+            self.0.range(range)
+        }
+    }
+
+    impl MapApi<u64> for Latest {
+        fn set(&mut self, key: u64, data: Option<u64>) -> (SeqMarked<u64>, SeqMarked<u64>) {
+            self.0.set(key, data)
+        }
+    }
+
+    fn seq(s: u64) -> InternalSeq {
+        InternalSeq::new(s)
+    }
+
+    fn sample_snapshot_ops() -> Vec<Op<u64, u64>> {
+        let mut ops = sample_ops();
+        ops.extend([
+            Op::GetAt(1, seq(2)),
+            Op::GetAt(1, seq(3)),
+            Op::GetAt(2, seq(0)),
+            Op::RangeAt(Bound::Unbounded, Bound::Unbounded, seq(1)),
+            Op::RangeAt(Bound::Excluded(1), Bound::Included(2), seq(3)),
+        ]);
+        ops
+    }
+
+    fn sample_ops() -> Vec<Op<u64, u64>> {
+        vec![
+            Op::Set(1, Some(10)),
+            Op::Set(2, Some(20)),
+            Op::Set(1, None),
+            Op::Get(2),
+            Op::Get(1),
+            Op::Range(Bound::Included(1), Bound::Unbounded),
+        ]
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut oracle = Oracle::default();
+        let mut map = BTreeMapApi::default();
+
+        replay(sample_ops(), &mut oracle, &mut map).unwrap();
+        assert_eq!(oracle.writes().len(), 3);
+    }
+
+    #[test]
+    fn test_replay_divergence() {
+        let mut oracle = Oracle::default();
+        let mut map = NoDelete::default();
+
+        let div = replay(sample_ops(), &mut oracle, &mut map).unwrap_err();
+        assert_eq!(div.index, 4);
+        assert_eq!(div.op, Op::Get(1));
+        assert_eq!(div.expected, Output::Get(SeqMarked::new_tombstone(3)));
+        assert_eq!(div.actual, Output::Get(norm(3, 10)));
+        assert_eq!(
+            div.to_string(),
+            "op #4 Get(1) diverged: expected Get(SeqMarked { seq: 3, marked: TombStone }), actual Get(SeqMarked { seq: 3, marked: Normal(10) })"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "reads at a snapshot must be replayed by replay_snapshot()")]
+    fn test_replay_snapshot_op() {
+        let mut oracle = Oracle::default();
+        let mut map = BTreeMapApi::default();
+
+        let _ = replay(sample_snapshot_ops(), &mut oracle, &mut map);
+    }
+
+    #[test]
+    fn test_replay_snapshot() {
+        let mut oracle = Oracle::default();
+        let mut map = Versioned::default();
+
+        replay_snapshot(sample_snapshot_ops(), &mut oracle, &mut map).unwrap();
+    }
+
+    #[test]
+    fn test_replay_snapshot_divergence() {
+        let mut oracle = Oracle::default();
+        let mut map = Latest::default();
+
+        let div = replay_snapshot(sample_snapshot_ops(), &mut oracle, &mut map).unwrap_err();
+        assert_eq!(div.index, 6);
+        assert_eq!(div.op, Op::GetAt(1, seq(2)));
+        assert_eq!(div.expected, Output::Get(norm(1, 10)));
+        assert_eq!(div.actual, Output::Get(SeqMarked::new_tombstone(3)));
+    }
+
+    #[test]
+    fn test_replay_leveled_view() {
+        let mut oracle = Oracle::default();
+        let mut lower = BTreeMapApi::default();
+        replay(sample_ops(), &mut oracle, &mut lower).unwrap();
+
+        let top = BTreeMapApi::default().with_last_seq(lower.last_seq());
+        let mut view = LeveledView::new(top, vec![lower]);
+
+        let ops = vec![
+            Op::Set(2, None),
+            Op::Set(3, Some(30)),
+            Op::Get(1),
+            Op::Get(2),
+            Op::Range(Bound::Unbounded, Bound::Excluded(3)),
+        ];
+        replay(ops, &mut oracle, &mut view).unwrap();
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "proptest")]
+mod tests_proptest {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::BTreeMapApi;
+    use crate::LeveledView;
+    use crate::testing::harness::tests::Versioned;
+
+    proptest! {
+        #[test]
+        fn test_replay_snapshot(
+            ops in snapshot_ops(0..8u64, any::<u8>().prop_map(u64::from), (0..40u64).prop_map(InternalSeq::new), 0..40),
+        ) {
+            let mut oracle = Oracle::default();
+            let mut map = Versioned::default();
+            replay_snapshot(ops, &mut oracle, &mut map).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        }
+
+        #[test]
+        fn test_replay_leveled_view(
+            lower_ops in ops(0..8u64, any::<u8>(), 0..30),
+            top_ops in ops(0..8u64, any::<u8>(), 0..30),
+        ) {
+            let mut oracle = Oracle::default();
+
+            let mut lower = BTreeMapApi::default();
+            replay(lower_ops, &mut oracle, &mut lower).map_err(|e| TestCaseError::fail(e.to_string()))?;
+
+            let top = BTreeMapApi::default().with_last_seq(lower.last_seq());
+            let mut view = LeveledView::new(top, vec![lower]);
+            replay(top_ops, &mut oracle, &mut view).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        }
+    }
+}
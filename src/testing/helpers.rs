@@ -1,4 +1,4 @@
-//! Testing utilities for seq_marked.
+//! Helpers for the unit tests of this crate.
 
 #![allow(dead_code)]
 
@@ -0,0 +1,29 @@
+//! Utilities for testing stores built upon this crate.
+//!
+//! [`Oracle`] is a trivially correct model of a versioned key-value store, and [`replay()`]
+//! replays an operation log against both the oracle and a [`MapApi`] implementation to find the
+//! first divergence. [`replay_snapshot()`] replays reads at a snapshot as well, against a
+//! [`MapApiSnapshot`] implementation.
+//!
+//! Enabled by feature `testing`.
+//!
+//! [`MapApi`]: crate::MapApi
+//! [`MapApiSnapshot`]: crate::MapApiSnapshot
+
+mod harness;
+#[cfg(test)]
+mod helpers;
+mod oracle;
+
+pub use harness::Divergence;
+pub use harness::Op;
+pub use harness::Output;
+#[cfg(feature = "proptest")]
+pub use harness::ops;
+pub use harness::replay;
+pub use harness::replay_snapshot;
+#[cfg(feature = "proptest")]
+pub use harness::snapshot_ops;
+#[cfg(test)]
+pub(crate) use helpers::*;
+pub use oracle::Oracle;
@@ -0,0 +1,249 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+use crate::InternalSeq;
+use crate::MapApi;
+use crate::MapApiRO;
+use crate::MapApiSnapshot;
+use crate::SeqMarked;
+
+/// A trivially correct model of a versioned key-value store, as a list of all writes in seq
+/// order.
+///
+/// Reads scan all writes, thus it is only suitable as a reference for testing.
+///
+/// ```rust
+/// use seq_marked::InternalSeq;
+/// use seq_marked::MapApi;
+/// use seq_marked::SeqMarked;
+/// use seq_marked::testing::Oracle;
+///
+/// let mut o = Oracle::default();
+/// o.set("a", Some("v1"));
+/// o.set("a", None);
+///
+/// assert_eq!(o.get_at(&"a", InternalSeq::new(1)), SeqMarked::new_normal(1, "v1"));
+/// assert_eq!(o.get_at(&"a", InternalSeq::new(2)), SeqMarked::new_tombstone(2));
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct Oracle<K, D = Vec<u8>> {
+    /// The seq of the last write.
+    last_seq: InternalSeq,
+    writes: Vec<(K, SeqMarked<D>)>,
+}
+
+impl<K, D> Default for Oracle<K, D> {
+    fn default() -> Self {
+        Self {
+            last_seq: InternalSeq::default(),
+            writes: vec![],
+        }
+    }
+}
+
+impl<K, D> Oracle<K, D> {
+    /// Sets the seq of the last write; the next write by [`set()`](MapApi::set) uses
+    /// `last_seq + 1`.
+    #[must_use]
+    pub fn with_last_seq(mut self, last_seq: InternalSeq) -> Self {
+        self.last_seq = last_seq;
+        self
+    }
+
+    /// Returns the seq of the last write.
+    pub fn last_seq(&self) -> InternalSeq {
+        self.last_seq
+    }
+
+    /// Returns all writes in seq order.
+    pub fn writes(&self) -> &[(K, SeqMarked<D>)] {
+        &self.writes
+    }
+
+    /// Appends a write with a seq assigned by the caller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the seq is not greater than the seq of the last write.
+    pub fn push(&mut self, key: K, value: SeqMarked<D>) {
+        assert!(
+            value.internal_seq() > self.last_seq,
+            "write seq {} must be greater than the last seq {}",
+            *value.internal_seq(),
+            *self.last_seq
+        );
+
+        self.last_seq = value.internal_seq();
+        self.writes.push((key, value));
+    }
+}
+
+impl<K, D> Oracle<K, D>
+where
+    K: Ord + Clone,
+    D: Clone,
+{
+    /// Returns the value of `key` visible at `snapshot`, i.e., the last write with seq `<=
+    /// snapshot`, or [`SeqMarked::new_not_found()`] if there is none.
+    pub fn get_at(&self, key: &K, snapshot: InternalSeq) -> SeqMarked<D> {
+        self.writes
+            .iter()
+            .filter(|(k, v)| k == key && v.internal_seq() <= snapshot)
+            .next_back()
+            .map(|(_, v)| v.clone())
+            .unwrap_or_else(SeqMarked::new_not_found)
+    }
+
+    /// Returns the values of keys in `range` visible at `snapshot`, sorted by key, including
+    /// tombstones.
+    pub fn range_at<R>(&self, range: R, snapshot: InternalSeq) -> Vec<(K, SeqMarked<D>)>
+    where R: RangeBounds<K> {
+        let mut latest = BTreeMap::new();
+
+        for (k, v) in &self.writes {
+            if range.contains(k) && v.internal_seq() <= snapshot {
+                latest.insert(k.clone(), v.clone());
+            }
+        }
+
+        latest.into_iter().collect()
+    }
+}
+
+impl<K, D> MapApiRO<K> for Oracle<K, D>
+where
+    K: Ord + Clone,
+    D: Clone,
+{
+    type Data = D;
+
+    fn get(&self, key: &K) -> SeqMarked<D> {
+        self.get_at(key, self.last_seq)
+    }
+
+    fn range<R>(&self, range: R) -> impl Iterator<Item = (K, SeqMarked<D>)>
+    where R: RangeBounds<K> {
+        self.range_at(range, self.last_seq).into_iter()
+    }
+}
+
+impl<K, D> MapApiSnapshot<K> for Oracle<K, D>
+where
+    K: Ord + Clone,
+    D: Clone,
+{
+    fn get_at(&self, key: &K, snapshot: InternalSeq) -> SeqMarked<D> {
+        Oracle::get_at(self, key, snapshot)
+    }
+
+    fn range_at<R>(
+        &self,
+        range: R,
+        snapshot: InternalSeq,
+    ) -> impl Iterator<Item = (K, SeqMarked<D>)>
+    where
+        R: RangeBounds<K>,
+    {
+        Oracle::range_at(self, range, snapshot).into_iter()
+    }
+}
+
+impl<K, D> MapApi<K> for Oracle<K, D>
+where
+    K: Ord + Clone,
+    D: Clone,
+{
+    /// Appends a write with seq `last_seq + 1`; `None` always writes a tombstone.
+    fn set(&mut self, key: K, data: Option<D>) -> (SeqMarked<D>, SeqMarked<D>) {
+        let prev = self.get(&key);
+
+        let seq = *self.last_seq + 1;
+        let result = match data {
+            Some(d) => SeqMarked::new_normal(seq, d),
+            None => SeqMarked::new_tombstone(seq),
+        };
+
+        self.push(key, result.clone());
+        (prev, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    fn seq(s: u64) -> InternalSeq {
+        InternalSeq::new(s)
+    }
+
+    #[test]
+    fn test_get_at() {
+        let mut o = Oracle::default();
+        assert_eq!(
+            o.set(1, Some("a")),
+            (SeqMarked::new_not_found(), norm(1, "a"))
+        );
+        assert_eq!(
+            o.set(2, Some("b")),
+            (SeqMarked::new_not_found(), norm(2, "b"))
+        );
+        assert_eq!(o.set(1, None), (norm(1, "a"), ts(3)));
+        assert_eq!(o.set(1, Some("c")), (ts(3), norm(4, "c")));
+
+        assert_eq!(o.last_seq(), seq(4));
+        assert_eq!(o.writes().len(), 4);
+
+        assert!(o.get_at(&1, seq(0)).is_not_found());
+        assert_eq!(o.get_at(&1, seq(2)), norm(1, "a"));
+        assert_eq!(o.get_at(&1, seq(3)), ts(3));
+        assert_eq!(o.get_at(&1, seq(10)), norm(4, "c"));
+        assert!(o.get_at(&3, seq(10)).is_not_found());
+
+        assert_eq!(o.get(&1), norm(4, "c"));
+    }
+
+    #[test]
+    fn test_range_at() {
+        let mut o = Oracle::default();
+        o.set(1, Some("a"));
+        o.set(3, Some("c"));
+        o.set(2, Some("b"));
+        o.set(1, None);
+
+        assert_eq!(o.range_at(.., seq(0)), vec![]);
+        assert_eq!(o.range_at(.., seq(2)), vec![
+            (1, norm(1, "a")),
+            (3, norm(2, "c"))
+        ]);
+        assert_eq!(o.range_at(..=2, seq(4)), vec![
+            (1, ts(4)),
+            (2, norm(3, "b"))
+        ]);
+
+        assert_eq!(o.range(2..).collect::<Vec<_>>(), vec![
+            (2, norm(3, "b")),
+            (3, norm(2, "c"))
+        ]);
+    }
+
+    #[test]
+    fn test_push() {
+        let mut o = Oracle::default().with_last_seq(seq(5));
+        o.push(1, norm(7, "a"));
+        assert_eq!(o.set(1, None), (norm(7, "a"), ts(8)));
+    }
+
+    #[test]
+    #[should_panic(expected = "write seq 7 must be greater than the last seq 7")]
+    fn test_push_out_of_order() {
+        let mut o = Oracle::default();
+        o.push(1, norm(7, "a"));
+        o.push(2, norm(7, "b"));
+    }
+}
@@ -0,0 +1,244 @@
+use alloc::collections::BTreeMap;
+use alloc::collections::btree_map::Entry;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::ops::RangeBounds;
+
+use crate::InternalSeq;
+use crate::SeqMarked;
+
+/// Multi-version in-memory map that keeps every version of a key as a [`SeqMarked`].
+///
+/// Versions are keyed by `(K, InternalSeq)`. A read at snapshot `s` observes the newest version
+/// whose seq is `<= s`, which may be a tombstone.
+///
+/// ```rust
+/// use seq_marked::InternalSeq;
+/// use seq_marked::SeqMarked;
+/// use seq_marked::VersionedMap;
+///
+/// let mut m = VersionedMap::new();
+/// m.insert("a", InternalSeq::new(1), "v1");
+/// m.delete("a", InternalSeq::new(3));
+///
+/// assert_eq!(m.get_at("a", InternalSeq::new(2)), Some(&SeqMarked::new_normal(1, "v1")));
+/// assert_eq!(m.get_at("a", InternalSeq::new(3)), Some(&SeqMarked::new_tombstone(3)));
+/// assert_eq!(m.get_at("a", InternalSeq::new(0)), None);
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct VersionedMap<K, D = Vec<u8>> {
+    map: BTreeMap<K, BTreeMap<InternalSeq, SeqMarked<D>>>,
+}
+
+impl<K, D> Default for VersionedMap<K, D> {
+    fn default() -> Self {
+        Self {
+            map: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K, D> VersionedMap<K, D>
+where K: Ord
+{
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the map contains no version.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Inserts a normal version of `key` at `seq`.
+    pub fn insert(&mut self, key: K, seq: InternalSeq, data: D) {
+        self.put(key, SeqMarked::new_normal(*seq, data));
+    }
+
+    /// Inserts a tombstone version of `key` at `seq`.
+    pub fn delete(&mut self, key: K, seq: InternalSeq) {
+        self.put(key, SeqMarked::new_tombstone(*seq));
+    }
+
+    /// Inserts a version of `key`.
+    ///
+    /// If a version with the same seq already exists, the one with the greater
+    /// [`SeqMarked::order_key`] is kept, so that a tombstone wins over a normal value.
+    pub fn put(&mut self, key: K, value: SeqMarked<D>) {
+        let versions = self.map.entry(key).or_default();
+
+        match versions.entry(value.internal_seq()) {
+            Entry::Vacant(e) => {
+                e.insert(value);
+            }
+            Entry::Occupied(mut e) => {
+                if value.order_key() > e.get().order_key() {
+                    e.insert(value);
+                }
+            }
+        }
+    }
+
+    /// Returns the newest version of `key` whose seq is `<= snapshot`.
+    ///
+    /// A tombstone is returned as is; `None` means no version is visible at `snapshot`.
+    pub fn get_at<Q>(&self, key: &Q, snapshot: InternalSeq) -> Option<&SeqMarked<D>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let versions = self.map.get(key)?;
+        versions.range(..=snapshot).next_back().map(|(_, v)| v)
+    }
+
+    /// Returns the newest version of every key in `range` whose seq is `<= snapshot`, sorted by
+    /// key.
+    ///
+    /// Tombstones are included so that the result can shadow lower levels.
+    pub fn range_at<Q, R>(
+        &self,
+        range: R,
+        snapshot: InternalSeq,
+    ) -> impl Iterator<Item = (&K, &SeqMarked<D>)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.map.range(range).filter_map(move |(k, versions)| {
+            let (_, v) = versions.range(..=snapshot).next_back()?;
+            Some((k, v))
+        })
+    }
+
+    /// Returns every version of every key, sorted by key then newest first, i.e., in the order
+    /// of [`InternalKey`](crate::InternalKey).
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &SeqMarked<D>)> {
+        self.map.iter().flat_map(|(k, versions)| versions.values().rev().map(move |v| (k, v)))
+    }
+
+    /// Returns all versions of `key`, newest first.
+    pub fn versions<Q>(&self, key: &Q) -> impl Iterator<Item = &SeqMarked<D>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get(key).into_iter().flat_map(|versions| versions.values().rev())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    fn s(seq: u64) -> InternalSeq {
+        InternalSeq::new(seq)
+    }
+
+    fn sample() -> VersionedMap<&'static str, &'static str> {
+        let mut m = VersionedMap::new();
+        m.insert("a", s(1), "a1");
+        m.insert("b", s(2), "b2");
+        m.insert("a", s(3), "a3");
+        m.delete("b", s(4));
+        m.insert("c", s(5), "c5");
+        m.insert("b", s(6), "b6");
+        m
+    }
+
+    #[test]
+    fn test_empty() {
+        let m = VersionedMap::<u64, u64>::new();
+        assert!(m.is_empty());
+        assert_eq!(m.get_at(&1, s(10)), None);
+        assert_eq!(m.range_at(.., s(10)).count(), 0);
+
+        assert!(!sample().is_empty());
+    }
+
+    #[test]
+    fn test_get_at() {
+        let m = sample();
+
+        assert_eq!(m.get_at("a", s(0)), None);
+        assert_eq!(m.get_at("a", s(1)), Some(&norm(1, "a1")));
+        assert_eq!(m.get_at("a", s(2)), Some(&norm(1, "a1")));
+        assert_eq!(m.get_at("a", s(3)), Some(&norm(3, "a3")));
+        assert_eq!(m.get_at("a", s(u64::MAX)), Some(&norm(3, "a3")));
+
+        assert_eq!(m.get_at("b", s(1)), None);
+        assert_eq!(m.get_at("b", s(3)), Some(&norm(2, "b2")));
+        assert_eq!(m.get_at("b", s(4)), Some(&ts(4)));
+        assert_eq!(m.get_at("b", s(5)), Some(&ts(4)));
+        assert_eq!(m.get_at("b", s(6)), Some(&norm(6, "b6")));
+
+        assert_eq!(m.get_at("x", s(10)), None);
+    }
+
+    #[test]
+    fn test_range_at() {
+        let m = sample();
+
+        let got = m.range_at::<str, _>(.., s(4)).collect::<Vec<_>>();
+        assert_eq!(got, vec![(&"a", &norm(3, "a3")), (&"b", &ts(4))]);
+
+        let got = m.range_at::<str, _>(.., s(6)).collect::<Vec<_>>();
+        assert_eq!(got, vec![
+            (&"a", &norm(3, "a3")),
+            (&"b", &norm(6, "b6")),
+            (&"c", &norm(5, "c5")),
+        ]);
+
+        let got = m.range_at("b".."c", s(6)).collect::<Vec<_>>();
+        assert_eq!(got, vec![(&"b", &norm(6, "b6"))]);
+
+        let got = m.range_at::<str, _>(.., s(0)).collect::<Vec<_>>();
+        assert_eq!(got, vec![]);
+    }
+
+    #[test]
+    fn test_versions() {
+        let m = sample();
+
+        let got = m.versions("b").collect::<Vec<_>>();
+        assert_eq!(got, vec![&norm(6, "b6"), &ts(4), &norm(2, "b2")]);
+
+        assert_eq!(m.versions("x").count(), 0);
+    }
+
+    #[test]
+    fn test_iter() {
+        let m = sample();
+
+        let got = m.iter().collect::<Vec<_>>();
+        assert_eq!(got, vec![
+            (&"a", &norm(3, "a3")),
+            (&"a", &norm(1, "a1")),
+            (&"b", &norm(6, "b6")),
+            (&"b", &ts(4)),
+            (&"b", &norm(2, "b2")),
+            (&"c", &norm(5, "c5")),
+        ]);
+    }
+
+    #[test]
+    fn test_put_same_seq_tombstone_wins() {
+        let mut m = VersionedMap::new();
+
+        m.insert(1, s(3), "a");
+        m.delete(1, s(3));
+        assert_eq!(m.get_at(&1, s(3)), Some(&ts(3)));
+
+        m.insert(1, s(3), "b");
+        assert_eq!(m.get_at(&1, s(3)), Some(&ts(3)));
+
+        m.insert(2, s(3), "a");
+        m.insert(2, s(3), "b");
+        assert_eq!(m.get_at(&2, s(3)), Some(&norm(3, "a")));
+    }
+}
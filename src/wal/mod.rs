@@ -0,0 +1,111 @@
+//! Write-ahead log of committed write batches.
+//!
+//! A log is a sequence of records, one per [`CommittedBatch`]. A record is:
+//!
+//! ```text
+//! | len: u32 | crc: u32 | payload: [u8; len] |
+//! ```
+//!
+//! - `len` is the big-endian length of the payload;
+//! - `crc` is the big-endian CRC32C of `len` and the payload;
+//! - `payload` is the [encoded](crate::CommittedBatch::encode) batch, which keeps the seq and the
+//!   tombstone state of every entry.
+//!
+//! ```rust
+//! use seq_marked::InternalSeq;
+//! use seq_marked::SeqMarked;
+//! use seq_marked::WriteBatch;
+//! use seq_marked::wal::WalReader;
+//! use seq_marked::wal::WalWriter;
+//!
+//! # let path = std::env::temp_dir().join(format!("seq-marked-wal-doc-{}", std::process::id()));
+//! let mut w = WalWriter::open(&path)?;
+//!
+//! let mut b = WriteBatch::new();
+//! b.put("a", "v1");
+//! b.delete("b");
+//! w.append(&b.commit(InternalSeq::new(1)))?;
+//!
+//! let r = WalReader::new(std::fs::File::open(&path)?);
+//! let batches = r.collect::<Result<Vec<_>, _>>()?;
+//! let entries = batches.into_iter().flatten().collect::<Vec<_>>();
+//! assert_eq!(entries, vec![
+//!     (b"a".to_vec(), SeqMarked::new_normal(1, b"v1".to_vec())),
+//!     (b"b".to_vec(), SeqMarked::new_tombstone(2)),
+//! ]);
+//! # std::fs::remove_file(&path)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Recovery
+//!
+//! After a crash, the log may end with a partial record, or contain corrupted ones.
+//! [`WalReader`] handles them according to a [`RecoveryMode`], and reports what is dropped in a
+//! [`RecoveryReport`].
+//!
+//! Before appending to a recovered log, it must be truncated to
+//! [`RecoveryReport::truncated_at`] with [`WalWriter::open_at()`]: otherwise new records are
+//! written after the corrupted ones, and can not be read back.
+//!
+//! [`CommittedBatch`]: crate::CommittedBatch
+
+mod reader;
+mod recovery;
+mod wal_error;
+mod writer;
+
+pub use reader::WalReader;
+pub use recovery::RecoveryMode;
+pub use recovery::RecoveryReport;
+pub use wal_error::WalError;
+pub use writer::WalWriter;
+
+/// Length of the record header: `len` and `crc`.
+pub const RECORD_HEADER_LEN: usize = 4 + 4;
+
+/// Returns the checksum of a record stored in the header.
+fn checksum(len: [u8; 4], payload: &[u8]) -> u32 {
+    crc32c::crc32c_append(crc32c::crc32c(&len), payload)
+}
+
+/// Builds a record of `payload`.
+fn encode_record(payload: &[u8]) -> Vec<u8> {
+    let len = u32::try_from(payload.len()).expect("WAL record exceeds u32::MAX").to_be_bytes();
+
+    let mut buf = Vec::with_capacity(RECORD_HEADER_LEN + payload.len());
+    buf.extend_from_slice(&len);
+    buf.extend_from_slice(&checksum(len, payload).to_be_bytes());
+    buf.extend_from_slice(payload);
+    buf
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+
+    /// Returns a unique path in the temp dir.
+    pub(crate) fn temp_path(name: &str) -> PathBuf {
+        static ID: AtomicU64 = AtomicU64::new(0);
+        let id = ID.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("seq-marked-{}-{}-{}", name, std::process::id(), id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_record() {
+        let r = encode_record(b"ab");
+        assert_eq!(r.len(), RECORD_HEADER_LEN + 2);
+        assert_eq!(&r[..4], &[0, 0, 0, 2]);
+        assert_eq!(&r[4..8], &checksum([0, 0, 0, 2], b"ab").to_be_bytes());
+        assert_eq!(&r[8..], b"ab");
+
+        // The length is covered by the checksum.
+        assert_ne!(checksum([0, 0, 0, 2], b"ab"), checksum([0, 0, 0, 3], b"ab"));
+    }
+}
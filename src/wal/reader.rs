@@ -0,0 +1,549 @@
+use std::io;
+use std::io::Read;
+
+use crate::CommittedBatch;
+use crate::InternalSeq;
+use crate::SeqRange;
+use crate::wal::RECORD_HEADER_LEN;
+use crate::wal::RecoveryMode;
+use crate::wal::RecoveryReport;
+use crate::wal::WalError;
+use crate::wal::checksum;
+
+/// A batch decoded from the log.
+type Batch = CommittedBatch<Vec<u8>, Vec<u8>>;
+
+/// Reads the [`CommittedBatch`]es of a write-ahead log, in the order they are appended.
+///
+/// Seqs must increase across records, an empty batch excepted.
+///
+/// A corrupted record is handled according to the [`RecoveryMode`]. With the default
+/// [`RecoveryMode::AbsoluteConsistency`], the iteration ends after the first error: records after
+/// a bad one are not read. What is dropped is recorded in the [`RecoveryReport`].
+///
+/// ```rust
+/// use seq_marked::wal::RecoveryMode;
+/// use seq_marked::wal::WalReader;
+///
+/// // A log ending with an incomplete record.
+/// let log = [0u8, 0, 0, 12, 0];
+///
+/// let mut r = WalReader::new(&log[..]).with_recovery_mode(RecoveryMode::TolerateCorruptedTail);
+/// assert_eq!(r.by_ref().count(), 0);
+/// assert_eq!(r.report().truncated_at, Some(0));
+/// ```
+#[derive(Debug)]
+pub struct WalReader<R> {
+    r: R,
+
+    mode: RecoveryMode,
+
+    /// The position of the next record.
+    offset: u64,
+
+    report: RecoveryReport,
+
+    /// The position of the first corrupted record since the last recovered one.
+    corrupted_since: Option<u64>,
+
+    /// Set if a record is skipped since the last recovered non-empty batch.
+    gap: bool,
+
+    /// Set after reaching the end or an error.
+    done: bool,
+}
+
+impl<R> WalReader<R>
+where R: Read
+{
+    /// Creates a reader of a log starting at the beginning of `r`.
+    pub fn new(r: R) -> Self {
+        Self {
+            r,
+            mode: RecoveryMode::default(),
+            offset: 0,
+            report: RecoveryReport::default(),
+            corrupted_since: None,
+            gap: false,
+            done: false,
+        }
+    }
+
+    /// Sets how to handle corrupted records.
+    #[must_use]
+    pub fn with_recovery_mode(mut self, mode: RecoveryMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn recovery_mode(&self) -> RecoveryMode {
+        self.mode
+    }
+
+    /// Returns the position of the next record to read.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the greatest seq read so far.
+    pub fn last_seq(&self) -> Option<InternalSeq> {
+        self.report.last_seq
+    }
+
+    /// Returns what is recovered and dropped so far; it is complete after the iteration ends.
+    pub fn report(&self) -> &RecoveryReport {
+        &self.report
+    }
+
+    /// Consumes self and returns the recovery report.
+    pub fn into_report(self) -> RecoveryReport {
+        self.report
+    }
+
+    /// Consumes self and returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+
+    /// Reads the next record; returns `Ok(None)` at the end of the log.
+    ///
+    /// A record that is read completely is consumed even if it is corrupted.
+    fn read_record(&mut self) -> Result<Option<Batch>, WalError> {
+        let offset = self.offset;
+
+        let mut header = [0u8; RECORD_HEADER_LEN];
+        let n = read_full(&mut self.r, &mut header)?;
+        if n == 0 {
+            return Ok(None);
+        }
+        if n < header.len() {
+            return Err(WalError::TornWrite { offset });
+        }
+
+        let len: [u8; 4] = header[..4].try_into().unwrap();
+        let expected = u32::from_be_bytes(header[4..].try_into().unwrap());
+
+        let payload_len = u32::from_be_bytes(len) as u64;
+        let mut payload = Vec::new();
+        (&mut self.r).take(payload_len).read_to_end(&mut payload)?;
+        if (payload.len() as u64) < payload_len {
+            return Err(WalError::TornWrite { offset });
+        }
+
+        self.offset += (RECORD_HEADER_LEN as u64) + payload_len;
+
+        let actual = checksum(len, &payload);
+        if actual != expected {
+            return Err(WalError::Checksum {
+                offset,
+                expected,
+                actual,
+            });
+        }
+
+        let batch = CommittedBatch::decode(&payload)
+            .map_err(|source| WalError::Decode { offset, source })?;
+
+        let range = batch.seq_range();
+        if let Some(batch_last) = range.last() {
+            if let Some(last) = self.report.last_seq {
+                if range.start() <= last {
+                    return Err(WalError::OutOfOrder {
+                        offset,
+                        last,
+                        first: range.start(),
+                    });
+                }
+
+                if self.gap && range.start() > last + 1 {
+                    self.report.lost.push(SeqRange::new(last + 1, range.start()));
+                }
+            }
+
+            self.gap = false;
+            self.report.last_seq = Some(batch_last);
+        }
+
+        self.corrupted_since = None;
+        Ok(Some(batch))
+    }
+
+    /// Handles an error according to the recovery mode, and returns the error to surface, if any.
+    fn recover(&mut self, err: WalError) -> Result<Recovery, WalError> {
+        let Some(offset) = err.offset() else {
+            return Err(err);
+        };
+
+        let recovery = match (self.mode, &err) {
+            (RecoveryMode::AbsoluteConsistency, _) => return Err(err),
+            (RecoveryMode::TolerateCorruptedTail, WalError::TornWrite { .. }) => Recovery::Stop,
+            (RecoveryMode::TolerateCorruptedTail, WalError::Checksum { .. }) => {
+                if read_full(&mut self.r, &mut [0u8; 1])? == 0 {
+                    Recovery::Stop
+                } else {
+                    return Err(err);
+                }
+            }
+            (RecoveryMode::TolerateCorruptedTail, _) => return Err(err),
+            (RecoveryMode::PointInTime, _) => Recovery::Stop,
+            (RecoveryMode::SkipCorrupted, WalError::TornWrite { .. }) => Recovery::Stop,
+            (RecoveryMode::SkipCorrupted, _) => Recovery::Skip,
+        };
+
+        let since = *self.corrupted_since.get_or_insert(offset);
+        if recovery == Recovery::Stop {
+            self.report.truncated_at = Some(since);
+        }
+
+        self.gap = true;
+        self.report.corruptions.push(err);
+        Ok(recovery)
+    }
+}
+
+/// What to do after a corrupted record.
+#[derive(PartialEq, Eq)]
+enum Recovery {
+    /// Continue with the next record.
+    Skip,
+
+    /// Drop the rest of the log.
+    Stop,
+}
+
+impl<R> Iterator for WalReader<R>
+where R: Read
+{
+    type Item = Result<Batch, WalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let err = match self.read_record() {
+                Ok(Some(batch)) => return Some(Ok(batch)),
+                Ok(None) => {
+                    self.done = true;
+                    self.report.truncated_at = self.corrupted_since;
+                    return None;
+                }
+                Err(err) => err,
+            };
+
+            match self.recover(err) {
+                Ok(Recovery::Skip) => {}
+                Ok(Recovery::Stop) => self.done = true,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Reads until `buf` is full or the end of `r`, and returns the number of bytes read.
+fn read_full(mut r: impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match r.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(m) => n += m,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::fs::OpenOptions;
+
+    use super::*;
+    use crate::DecodeError;
+    use crate::SeqMarked;
+    use crate::WriteBatch;
+    use crate::wal::WalWriter;
+    use crate::wal::encode_record;
+    use crate::wal::testing::temp_path;
+
+    fn batch(first_seq: u64, keys: &[&str]) -> Batch {
+        let mut b = WriteBatch::new();
+        for k in keys {
+            b.put(k.as_bytes().to_vec(), b"v".to_vec());
+        }
+        b.commit(InternalSeq::new(first_seq))
+    }
+
+    fn read_all(buf: &[u8]) -> (Vec<Batch>, Option<WalError>) {
+        let mut batches = vec![];
+        for res in WalReader::new(buf) {
+            match res {
+                Ok(b) => batches.push(b),
+                Err(e) => return (batches, Some(e)),
+            }
+        }
+        (batches, None)
+    }
+
+    fn records(batches: &[Batch]) -> Vec<u8> {
+        batches.iter().flat_map(|b| encode_record(&b.encode())).collect()
+    }
+
+    #[test]
+    fn test_write_read_file() -> anyhow::Result<()> {
+        let path = temp_path("wal-reader");
+
+        let mut b = WriteBatch::new();
+        b.put("a", "1");
+        b.delete("b");
+
+        let mut w = WalWriter::open(&path)?;
+        w.append(&b.commit(InternalSeq::new(1)))?;
+        w.append(&WriteBatch::<&str, &str>::new().commit(InternalSeq::new(3)))?;
+        w.append(&batch(3, &["c"]))?;
+
+        let mut r = WalReader::new(File::open(&path)?);
+        let got = r.by_ref().collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(got.len(), 3);
+        assert_eq!(got[0].iter().collect::<Vec<_>>(), vec![
+            (&b"a".to_vec(), SeqMarked::new_normal(1, &b"1".to_vec())),
+            (&b"b".to_vec(), SeqMarked::new_tombstone(2)),
+        ]);
+        assert!(got[1].is_empty());
+        assert_eq!(got[2], batch(3, &["c"]));
+
+        assert_eq!(r.offset(), w.offset());
+        assert_eq!(r.last_seq(), Some(InternalSeq::new(3)));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_log() {
+        let (got, err) = read_all(&[]);
+        assert!(got.is_empty());
+        assert!(err.is_none());
+    }
+
+    #[test]
+    fn test_torn_write() -> anyhow::Result<()> {
+        let path = temp_path("wal-torn");
+
+        let mut w = WalWriter::open(&path)?;
+        w.append(&batch(1, &["a"]))?;
+        let end = w.offset();
+        w.append(&batch(2, &["b"]))?;
+
+        // Truncate the last record in the payload, then in the header.
+        for len in [w.offset() - 1, end + 3] {
+            OpenOptions::new().write(true).open(&path)?.set_len(len)?;
+
+            let mut r = WalReader::new(File::open(&path)?);
+            assert_eq!(r.next().unwrap()?, batch(1, &["a"]));
+
+            let err = r.next().unwrap().unwrap_err();
+            assert!(matches!(err, WalError::TornWrite { offset } if offset == end));
+            assert!(r.next().is_none());
+            assert_eq!(r.offset(), end);
+        }
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_checksum() {
+        let mut buf = records(&[batch(1, &["a"]), batch(2, &["b"])]);
+        let n = buf.len();
+        buf[n - 1] ^= 0xff;
+
+        let (got, err) = read_all(&buf);
+        assert_eq!(got, vec![batch(1, &["a"])]);
+        assert!(matches!(err, Some(WalError::Checksum { offset, .. }) if offset == n as u64 / 2));
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let buf = encode_record(b"foo");
+
+        let (got, err) = read_all(&buf);
+        assert!(got.is_empty());
+        assert!(matches!(
+            err,
+            Some(WalError::Decode {
+                offset: 0,
+                source: DecodeError::TooShort { .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_out_of_order() {
+        let buf = records(&[batch(1, &["a", "b"]), batch(2, &["c"])]);
+
+        let (got, err) = read_all(&buf);
+        assert_eq!(got, vec![batch(1, &["a", "b"])]);
+        assert!(matches!(err, Some(WalError::OutOfOrder { last, first, .. })
+            if *last == 2 && *first == 2));
+    }
+
+    /// Build a log of 4 records with seq `1`, `2..4`, `4`, `5`, and corrupt the `bad` ones.
+    ///
+    /// Returns the log and the positions of the records.
+    fn corrupted_log(bad: &[usize]) -> (Vec<u8>, Vec<u64>) {
+        let batches = [
+            batch(1, &["a"]),
+            batch(2, &["b", "c"]),
+            batch(4, &["d"]),
+            batch(5, &["e"]),
+        ];
+
+        let mut buf = vec![];
+        let mut offsets = vec![];
+        for (i, b) in batches.iter().enumerate() {
+            offsets.push(buf.len() as u64);
+            let mut rec = encode_record(&b.encode());
+            if bad.contains(&i) {
+                *rec.last_mut().unwrap() ^= 0xff;
+            }
+            buf.extend_from_slice(&rec);
+        }
+        (buf, offsets)
+    }
+
+    fn recover(buf: &[u8], mode: RecoveryMode) -> (Vec<u64>, Option<WalError>, RecoveryReport) {
+        let mut r = WalReader::new(buf).with_recovery_mode(mode);
+        assert_eq!(r.recovery_mode(), mode);
+
+        let mut firsts = vec![];
+        let mut err = None;
+        for res in r.by_ref() {
+            match res {
+                Ok(b) => firsts.push(*b.seq_range().start()),
+                Err(e) => err = Some(e),
+            }
+        }
+        (firsts, err, r.into_report())
+    }
+
+    #[test]
+    fn test_recover_clean() {
+        let (buf, _) = corrupted_log(&[]);
+
+        for mode in [
+            RecoveryMode::TolerateCorruptedTail,
+            RecoveryMode::PointInTime,
+            RecoveryMode::SkipCorrupted,
+            RecoveryMode::AbsoluteConsistency,
+        ] {
+            let (got, err, report) = recover(&buf, mode);
+            assert_eq!(got, vec![1, 2, 4, 5]);
+            assert!(err.is_none());
+            assert!(report.is_clean());
+            assert_eq!(report.last_seq, Some(InternalSeq::new(5)));
+            assert_eq!(report.truncated_at, None);
+        }
+    }
+
+    #[test]
+    fn test_recover_torn_tail() {
+        let (mut buf, offsets) = corrupted_log(&[]);
+        buf.truncate(buf.len() - 1);
+
+        for mode in [
+            RecoveryMode::TolerateCorruptedTail,
+            RecoveryMode::PointInTime,
+            RecoveryMode::SkipCorrupted,
+        ] {
+            let (got, err, report) = recover(&buf, mode);
+            assert_eq!(got, vec![1, 2, 4]);
+            assert!(err.is_none());
+            assert_eq!(report.corruptions.len(), 1);
+            assert_eq!(report.last_seq, Some(InternalSeq::new(4)));
+            assert!(report.lost.is_empty());
+            assert_eq!(report.truncated_at, Some(offsets[3]));
+        }
+
+        let (got, err, report) = recover(&buf, RecoveryMode::AbsoluteConsistency);
+        assert_eq!(got, vec![1, 2, 4]);
+        assert!(matches!(err, Some(WalError::TornWrite { offset }) if offset == offsets[3]));
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_recover_corrupted_tail() {
+        let (buf, offsets) = corrupted_log(&[3]);
+
+        let (got, err, report) = recover(&buf, RecoveryMode::TolerateCorruptedTail);
+        assert_eq!(got, vec![1, 2, 4]);
+        assert!(err.is_none());
+        assert_eq!(report.corruptions[0].offset(), Some(offsets[3]));
+        assert_eq!(report.truncated_at, Some(offsets[3]));
+
+        let (got, err, _) = recover(&buf, RecoveryMode::AbsoluteConsistency);
+        assert_eq!(got, vec![1, 2, 4]);
+        assert!(matches!(err, Some(WalError::Checksum { .. })));
+    }
+
+    #[test]
+    fn test_recover_corrupted_middle() {
+        let (buf, offsets) = corrupted_log(&[1]);
+
+        // Only a corrupted last record is tolerated.
+        let (got, err, report) = recover(&buf, RecoveryMode::TolerateCorruptedTail);
+        assert_eq!(got, vec![1]);
+        assert_eq!(err.unwrap().offset(), Some(offsets[1]));
+        assert!(report.is_clean());
+
+        let (got, err, report) = recover(&buf, RecoveryMode::PointInTime);
+        assert_eq!(got, vec![1]);
+        assert!(err.is_none());
+        assert_eq!(report.corruptions.len(), 1);
+        assert_eq!(report.last_seq, Some(InternalSeq::new(1)));
+        assert!(report.lost.is_empty());
+        assert_eq!(report.truncated_at, Some(offsets[1]));
+
+        let (got, err, report) = recover(&buf, RecoveryMode::SkipCorrupted);
+        assert_eq!(got, vec![1, 4, 5]);
+        assert!(err.is_none());
+        assert_eq!(report.corruptions.len(), 1);
+        assert_eq!(report.last_seq, Some(InternalSeq::new(5)));
+        assert_eq!(report.lost, vec![SeqRange::new(
+            InternalSeq::new(2),
+            InternalSeq::new(4)
+        )]);
+        assert_eq!(report.truncated_at, None);
+    }
+
+    #[test]
+    fn test_recover_skip_to_tail() {
+        let (buf, offsets) = corrupted_log(&[0, 2, 3]);
+
+        let (got, err, report) = recover(&buf, RecoveryMode::SkipCorrupted);
+        assert_eq!(got, vec![2]);
+        assert!(err.is_none());
+        assert_eq!(report.corruptions.len(), 3);
+        assert_eq!(report.last_seq, Some(InternalSeq::new(3)));
+
+        // Seqs before the first recovered record are unknown.
+        assert!(report.lost.is_empty());
+        assert_eq!(report.truncated_at, Some(offsets[2]));
+    }
+
+    #[test]
+    fn test_recover_out_of_order() {
+        let buf = records(&[batch(1, &["a", "b"]), batch(2, &["c"]), batch(3, &["d"])]);
+
+        let (got, err, report) = recover(&buf, RecoveryMode::SkipCorrupted);
+        assert_eq!(got, vec![1, 3]);
+        assert!(err.is_none());
+        assert!(matches!(report.corruptions[..], [
+            WalError::OutOfOrder { .. }
+        ]));
+        assert!(report.lost.is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::InternalSeq;
+use crate::SeqRange;
+use crate::wal::WalError;
+
+/// How a [`WalReader`](crate::wal::WalReader) handles a corrupted record.
+///
+/// An I/O error of the underlying reader is always returned, regardless of the mode.
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+pub enum RecoveryMode {
+    /// Drops an incomplete or checksum-mismatched last record, e.g., left by a crash during the
+    /// last write; returns an error for corruption elsewhere.
+    TolerateCorruptedTail,
+
+    /// Stops at the first corrupted record without returning an error, recovering the log up to
+    /// [`RecoveryReport::last_seq`].
+    PointInTime,
+
+    /// Skips corrupted records and continues with the next one.
+    ///
+    /// A record is located by the length in its header: after a corrupted length, the following
+    /// records are likely dropped too.
+    SkipCorrupted,
+
+    /// Returns an error for any corrupted record, including an incomplete last record.
+    #[default]
+    AbsoluteConsistency,
+}
+
+/// What a [`WalReader`](crate::wal::WalReader) recovers and what it drops.
+///
+/// A caller may re-fetch the lost seqs, e.g., from a replica.
+///
+/// If [`truncated_at`](Self::truncated_at) is `Some`, the log must be truncated to it before
+/// appending, with [`WalWriter::open_at()`](crate::wal::WalWriter::open_at).
+#[derive(Debug)]
+#[derive(Default)]
+pub struct RecoveryReport {
+    /// The greatest seq of the recovered records.
+    pub last_seq: Option<InternalSeq>,
+
+    /// The corrupted records that are skipped or dropped, in the order of the log.
+    pub corruptions: Vec<WalError>,
+
+    /// The seqs lost between recovered records, because the records in between are skipped.
+    ///
+    /// Seqs lost before the first recovered record are unknown and not listed.
+    pub lost: Vec<SeqRange>,
+
+    /// The position from which the rest of the log is dropped.
+    ///
+    /// If it is `Some`, seqs greater than [`last_seq`](Self::last_seq) may be lost.
+    pub truncated_at: Option<u64>,
+}
+
+impl RecoveryReport {
+    /// Returns `true` if nothing is dropped: no corrupted record, no lost seq and no truncation.
+    pub fn is_clean(&self) -> bool {
+        self.corruptions.is_empty() && self.lost.is_empty() && self.truncated_at.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_clean() {
+        assert!(RecoveryReport::default().is_clean());
+
+        let r = RecoveryReport {
+            truncated_at: Some(3),
+            ..Default::default()
+        };
+        assert!(!r.is_clean());
+
+        let r = RecoveryReport {
+            lost: vec![SeqRange::new(InternalSeq::new(1), InternalSeq::new(2))],
+            ..Default::default()
+        };
+        assert!(!r.is_clean());
+
+        let r = RecoveryReport {
+            corruptions: vec![WalError::TornWrite { offset: 0 }],
+            ..Default::default()
+        };
+        assert!(!r.is_clean());
+    }
+}
@@ -0,0 +1,133 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::DecodeError;
+use crate::InternalSeq;
+
+/// Error reading a write-ahead log.
+///
+/// `offset` is the position of the record in the log.
+#[derive(Debug)]
+pub enum WalError {
+    Io(io::Error),
+
+    /// The log ends in the middle of a record, e.g., the last write is interrupted.
+    TornWrite {
+        offset: u64,
+    },
+
+    /// The checksum of a record does not match its content.
+    Checksum {
+        offset: u64,
+        expected: u32,
+        actual: u32,
+    },
+
+    /// The payload of a record is not a valid batch.
+    Decode {
+        offset: u64,
+        source: DecodeError,
+    },
+
+    /// The seqs of a record are not greater than those of the previous record.
+    OutOfOrder {
+        offset: u64,
+        last: InternalSeq,
+        first: InternalSeq,
+    },
+}
+
+impl WalError {
+    /// Returns the position of the bad record, or `None` for an I/O error.
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            WalError::Io(_) => None,
+            WalError::TornWrite { offset }
+            | WalError::Checksum { offset, .. }
+            | WalError::Decode { offset, .. }
+            | WalError::OutOfOrder { offset, .. } => Some(*offset),
+        }
+    }
+}
+
+impl From<io::Error> for WalError {
+    fn from(e: io::Error) -> Self {
+        WalError::Io(e)
+    }
+}
+
+impl fmt::Display for WalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalError::Io(e) => write!(f, "WAL I/O error: {}", e),
+            WalError::TornWrite { offset } => {
+                write!(f, "WAL torn write: incomplete record at offset {}", offset)
+            }
+            WalError::Checksum {
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "WAL checksum mismatch at offset {}: expected {:#010x}, actual {:#010x}",
+                offset, expected, actual
+            ),
+            WalError::Decode { offset, source } => {
+                write!(f, "WAL invalid record at offset {}: {}", offset, source)
+            }
+            WalError::OutOfOrder {
+                offset,
+                last,
+                first,
+            } => write!(
+                f,
+                "WAL record out of order at offset {}: seq {} after {}",
+                offset, **first, **last
+            ),
+        }
+    }
+}
+
+impl Error for WalError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WalError::Io(e) => Some(e),
+            WalError::Decode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let e = WalError::Checksum {
+            offset: 8,
+            expected: 1,
+            actual: 0xab,
+        };
+        assert_eq!(
+            e.to_string(),
+            "WAL checksum mismatch at offset 8: expected 0x00000001, actual 0x000000ab"
+        );
+        assert_eq!(e.offset(), Some(8));
+
+        let e = WalError::OutOfOrder {
+            offset: 3,
+            last: InternalSeq::new(5),
+            first: InternalSeq::new(4),
+        };
+        assert_eq!(
+            e.to_string(),
+            "WAL record out of order at offset 3: seq 4 after 5"
+        );
+
+        let e = WalError::from(io::Error::other("foo"));
+        assert_eq!(e.offset(), None);
+        assert!(e.source().is_some());
+    }
+}
@@ -0,0 +1,227 @@
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+
+use crate::CommittedBatch;
+use crate::wal::encode_record;
+
+/// Appends [`CommittedBatch`]es to a write-ahead log file.
+///
+/// Every append is flushed to disk with [`File::sync_data()`] before it returns, so that an
+/// acknowledged batch survives a crash.
+///
+/// A failed append is rolled back, so that a partial record is never followed by good ones.
+#[derive(Debug)]
+pub struct WalWriter {
+    file: File,
+
+    /// The length of the log, i.e., the position of the next record.
+    offset: u64,
+
+    /// Set if a failed append can not be rolled back; no more append is accepted.
+    broken: bool,
+}
+
+impl WalWriter {
+    /// Opens the log at `path` for appending at its end, creating it if absent.
+    ///
+    /// The log must end with a complete record. To append to a log recovered by
+    /// [`WalReader`](crate::wal::WalReader), use [`open_at()`](Self::open_at) instead.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).truncate(false).write(true).open(path)?;
+        let len = file.metadata()?.len();
+        Self::new(file, len)
+    }
+
+    /// Opens the log at `path`, creating it if absent, and truncates it to `len` before appending.
+    ///
+    /// `len` is the length of the valid part of the log, i.e.,
+    /// [`RecoveryReport::truncated_at`](crate::wal::RecoveryReport::truncated_at) if it is `Some`,
+    /// otherwise the length of the log. Records appended after a corrupted tail without
+    /// truncating it can not be read back.
+    pub fn open_at(path: impl AsRef<Path>, len: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).truncate(false).write(true).open(path)?;
+
+        let file_len = file.metadata()?.len();
+        if len > file_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "WAL truncate length {} exceeds the file length {}",
+                    len, file_len
+                ),
+            ));
+        }
+
+        if len < file_len {
+            file.set_len(len)?;
+            file.sync_all()?;
+        }
+        Self::new(file, len)
+    }
+
+    fn new(mut file: File, offset: u64) -> io::Result<Self> {
+        file.seek(SeekFrom::Start(offset))?;
+        Ok(Self {
+            file,
+            offset,
+            broken: false,
+        })
+    }
+
+    /// Returns the length of the log, i.e., the position of the next record.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Appends a batch as one record and syncs it to disk.
+    ///
+    /// Returns the position of the record.
+    pub fn append<K, D>(&mut self, batch: &CommittedBatch<K, D>) -> io::Result<u64>
+    where
+        K: AsRef<[u8]>,
+        D: AsRef<[u8]>,
+    {
+        self.append_many([batch])
+    }
+
+    /// Appends batches as one record each, and syncs them to disk at once.
+    ///
+    /// Returns the position of the first record.
+    pub fn append_many<'a, K, D>(
+        &mut self,
+        batches: impl IntoIterator<Item = &'a CommittedBatch<K, D>>,
+    ) -> io::Result<u64>
+    where
+        K: AsRef<[u8]> + 'a,
+        D: AsRef<[u8]> + 'a,
+    {
+        let mut buf = Vec::new();
+        for batch in batches {
+            buf.extend_from_slice(&encode_record(&batch.encode()));
+        }
+
+        if self.broken {
+            return Err(io::Error::other(
+                "WAL writer is broken by a failed append that can not be rolled back",
+            ));
+        }
+
+        let offset = self.offset;
+        let res = self.file.write_all(&buf).and_then(|_| self.file.sync_data());
+
+        if let Err(e) = res {
+            if self.rollback().is_err() {
+                self.broken = true;
+            }
+            return Err(e);
+        }
+
+        self.offset += buf.len() as u64;
+        Ok(offset)
+    }
+
+    /// Removes a partially written record after the last good one.
+    fn rollback(&mut self) -> io::Result<()> {
+        self.file.set_len(self.offset)?;
+        self.file.sync_all()?;
+        self.file.seek(SeekFrom::Start(self.offset))?;
+        Ok(())
+    }
+
+    /// Consumes self and returns the underlying file.
+    pub fn into_inner(self) -> File {
+        self.file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommittedBatch;
+    use crate::InternalSeq;
+    use crate::WriteBatch;
+    use crate::wal::RECORD_HEADER_LEN;
+    use crate::wal::RecoveryMode;
+    use crate::wal::WalReader;
+    use crate::wal::testing::temp_path;
+
+    fn batch(seq: u64, key: &str) -> CommittedBatch<Vec<u8>, Vec<u8>> {
+        let mut b = WriteBatch::new();
+        b.put(key.as_bytes().to_vec(), b"v".to_vec());
+        b.commit(InternalSeq::new(seq))
+    }
+
+    #[test]
+    fn test_append() -> anyhow::Result<()> {
+        let path = temp_path("wal-writer");
+
+        let mut b = WriteBatch::new();
+        b.put("a", "1");
+        let b = b.commit(InternalSeq::new(1));
+        let len = (RECORD_HEADER_LEN + b.encoded_len()) as u64;
+
+        let mut w = WalWriter::open(&path)?;
+        assert_eq!(w.offset(), 0);
+        assert_eq!(w.append(&b)?, 0);
+        assert_eq!(w.offset(), len);
+        assert_eq!(w.append_many([&b, &b])?, len);
+        assert_eq!(w.offset(), 3 * len);
+        drop(w);
+
+        // Reopening continues at the end.
+        let w = WalWriter::open(&path)?;
+        assert_eq!(w.offset(), 3 * len);
+        assert_eq!(w.into_inner().metadata()?.len(), 3 * len);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_after_torn_tail() -> anyhow::Result<()> {
+        let path = temp_path("wal-writer-torn");
+
+        let mut w = WalWriter::open(&path)?;
+        w.append(&batch(1, "a"))?;
+        w.append(&batch(2, "b"))?;
+        let len = w.offset();
+        drop(w);
+
+        OpenOptions::new().write(true).open(&path)?.set_len(len - 2)?;
+
+        let mut r = WalReader::new(File::open(&path)?)
+            .with_recovery_mode(RecoveryMode::TolerateCorruptedTail);
+        assert_eq!(r.by_ref().count(), 1);
+        let truncated_at = r.report().truncated_at.unwrap();
+
+        let mut w = WalWriter::open_at(&path, truncated_at)?;
+        assert_eq!(w.offset(), truncated_at);
+        w.append(&batch(2, "c"))?;
+        w.append(&batch(3, "d"))?;
+        drop(w);
+
+        let got = WalReader::new(File::open(&path)?).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(got, vec![batch(1, "a"), batch(2, "c"), batch(3, "d"),]);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_at_beyond_end() -> anyhow::Result<()> {
+        let path = temp_path("wal-writer-beyond");
+
+        WalWriter::open(&path)?.append(&batch(1, "a"))?;
+
+        let err = WalWriter::open_at(&path, 1000).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+//! Binary encoding of a committed batch, e.g., as the payload of a WAL record.
+//!
+//! The encoding is the big-endian `first_seq: u64` and `count: u32`, followed by `count` entries.
+//! An entry is a mark byte, `0` for put and `1` for delete, then the `u32` length-prefixed key,
+//! then the `u32` length-prefixed data for a put.
+
+use alloc::vec::Vec;
+
+use crate::CommittedBatch;
+use crate::DecodeError;
+use crate::InternalSeq;
+use crate::Marked;
+use crate::codec::Reader;
+use crate::codec::put_bytes;
+use crate::codec::put_len;
+
+const PUT: u8 = 0;
+const DELETE: u8 = 1;
+
+/// Length of `first_seq` and `count`.
+const HEADER_LEN: usize = 8 + 4;
+
+impl<K, D> CommittedBatch<K, D>
+where
+    K: AsRef<[u8]>,
+    D: AsRef<[u8]>,
+{
+    /// Returns the length of the encoded form.
+    pub fn encoded_len(&self) -> usize {
+        let entries = self.ops.iter().map(|(k, m)| {
+            let data_len = match m {
+                Marked::Normal(d) => 4 + d.as_ref().len(),
+                Marked::TombStone => 0,
+            };
+            1 + 4 + k.as_ref().len() + data_len
+        });
+
+        HEADER_LEN + entries.sum::<usize>()
+    }
+
+    /// Encodes into bytes, which are decoded by [`decode()`](CommittedBatch::decode).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `u32::MAX` operations, or a key or data is longer than
+    /// `u32::MAX` bytes.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.encoded_len());
+
+        buf.extend_from_slice(&self.first_seq.to_be_bytes());
+        put_len(&mut buf, self.ops.len());
+
+        for (k, m) in &self.ops {
+            match m {
+                Marked::Normal(d) => {
+                    buf.push(PUT);
+                    put_bytes(&mut buf, k.as_ref());
+                    put_bytes(&mut buf, d.as_ref());
+                }
+                Marked::TombStone => {
+                    buf.push(DELETE);
+                    put_bytes(&mut buf, k.as_ref());
+                }
+            }
+        }
+
+        buf
+    }
+}
+
+impl CommittedBatch<Vec<u8>, Vec<u8>> {
+    /// Decodes bytes built by [`encode()`](CommittedBatch::encode).
+    pub fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader::new(buf);
+
+        let first_seq = u64::from_be_bytes(r.take_array()?);
+        let count = u32::from_be_bytes(r.take_array()?);
+
+        if first_seq.checked_add(count as u64).is_none() {
+            return Err(DecodeError::SeqOverflow {
+                first_seq,
+                count: count as u64,
+            });
+        }
+
+        let mut ops = Vec::new();
+        for _ in 0..count {
+            let [mark] = r.take_array()?;
+            let key = r.take_bytes()?;
+
+            let m = match mark {
+                PUT => Marked::Normal(r.take_bytes()?),
+                DELETE => Marked::TombStone,
+                b => return Err(DecodeError::InvalidMark(b)),
+            };
+            ops.push((key, m));
+        }
+
+        if !r.is_empty() {
+            return Err(DecodeError::InvalidLength {
+                expected: buf.len() - r.remaining().len(),
+                actual: buf.len(),
+            });
+        }
+
+        Ok(Self::new(InternalSeq::new(first_seq), ops))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WriteBatch;
+
+    fn sample() -> CommittedBatch<Vec<u8>, Vec<u8>> {
+        let mut b = WriteBatch::new();
+        b.put(b"a".to_vec(), b"xy".to_vec());
+        b.delete(b"b".to_vec());
+        b.put(b"a".to_vec(), vec![]);
+        b.commit(InternalSeq::new(0x0102))
+    }
+
+    #[test]
+    fn test_encode() {
+        let c = sample();
+        let encoded = c.encode();
+
+        assert_eq!(encoded.len(), c.encoded_len());
+        assert_eq!(encoded, vec![
+            0, 0, 0, 0, 0, 0, 1, 2, // first_seq
+            0, 0, 0, 3, // count
+            0, 0, 0, 0, 1, b'a', 0, 0, 0, 2, b'x', b'y', // put
+            1, 0, 0, 0, 1, b'b', // delete
+            0, 0, 0, 0, 1, b'a', 0, 0, 0, 0, // put empty
+        ]);
+
+        assert_eq!(CommittedBatch::decode(&encoded).unwrap(), c);
+    }
+
+    #[test]
+    fn test_encode_borrowed() {
+        let mut b = WriteBatch::new();
+        b.put("a", "xy");
+        b.delete("b");
+        b.put("a", "");
+        let c = b.commit(InternalSeq::new(0x0102));
+
+        assert_eq!(c.encode(), sample().encode());
+    }
+
+    #[test]
+    fn test_decode_empty() {
+        let c = WriteBatch::<&str, &str>::new().commit(InternalSeq::new(3));
+        let decoded = CommittedBatch::decode(&c.encode()).unwrap();
+
+        assert!(decoded.is_empty());
+        assert_eq!(decoded.seq_range().start(), InternalSeq::new(3));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let encoded = sample().encode();
+
+        // Every truncation is detected.
+        for i in 0..encoded.len() {
+            let err = CommittedBatch::decode(&encoded[..i]).unwrap_err();
+            assert!(
+                matches!(err, DecodeError::TooShort { .. }),
+                "{}: {}",
+                i,
+                err
+            );
+        }
+
+        let mut b = encoded.clone();
+        b[12] = 2;
+        assert_eq!(
+            CommittedBatch::decode(&b).unwrap_err(),
+            DecodeError::InvalidMark(2)
+        );
+
+        // The last seq is u64::MAX - 1, as the end of the range is exclusive.
+        let mut b = encoded.clone();
+        b[..8].copy_from_slice(&(u64::MAX - 3).to_be_bytes());
+        let decoded = CommittedBatch::decode(&b).unwrap();
+        assert_eq!(
+            decoded.seq_range().last(),
+            Some(InternalSeq::new(u64::MAX - 1))
+        );
+
+        let mut b = encoded.clone();
+        b[..8].copy_from_slice(&(u64::MAX - 2).to_be_bytes());
+        assert_eq!(
+            CommittedBatch::decode(&b).unwrap_err(),
+            DecodeError::SeqOverflow {
+                first_seq: u64::MAX - 2,
+                count: 3
+            }
+        );
+
+        let mut b = encoded.clone();
+        b.push(0);
+        assert_eq!(
+            CommittedBatch::decode(&b).unwrap_err(),
+            DecodeError::InvalidLength {
+                expected: 40,
+                actual: 41
+            }
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use alloc::vec::Vec;
+
+use crate::InternalSeq;
+use crate::Marked;
+use crate::SeqMarked;
+use crate::SeqRange;
+
+/// A [`WriteBatch`](crate::WriteBatch) whose operations are assigned seqs.
+///
+/// It yields `(K, SeqMarked<D>)` entries in the order of the operations, to apply to a memtable
+/// or to write to a WAL.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct CommittedBatch<K, D = Vec<u8>> {
+    /// The seq of the first operation.
+    pub(crate) first_seq: InternalSeq,
+    pub(crate) ops: Vec<(K, Marked<D>)>,
+}
+
+impl<K, D> CommittedBatch<K, D> {
+    pub(crate) fn new(first_seq: InternalSeq, ops: Vec<(K, Marked<D>)>) -> Self {
+        Self { first_seq, ops }
+    }
+
+    /// Returns the seqs assigned to the operations.
+    pub fn seq_range(&self) -> SeqRange {
+        SeqRange::new(self.first_seq, self.first_seq + self.ops.len() as u64)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Returns the entries by reference, in the order of the operations.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, SeqMarked<&D>)> {
+        self.ops.iter().enumerate().map(|(i, (k, m))| {
+            let m = match m {
+                Marked::Normal(d) => Marked::Normal(d),
+                Marked::TombStone => Marked::TombStone,
+            };
+            (k, SeqMarked::new(*self.first_seq + i as u64, m))
+        })
+    }
+
+    /// Returns the entry of the last operation on `key`, which shadows the earlier ones.
+    pub fn get(&self, key: &K) -> Option<SeqMarked<&D>>
+    where K: PartialEq {
+        self.iter().filter(|(k, _)| *k == key).map(|(_, v)| v).last()
+    }
+}
+
+impl<K, D> IntoIterator for CommittedBatch<K, D> {
+    type Item = (K, SeqMarked<D>);
+    type IntoIter = IntoIter<K, D>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            next_seq: self.first_seq,
+            ops: self.ops.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the entries of a [`CommittedBatch`].
+pub struct IntoIter<K, D> {
+    next_seq: InternalSeq,
+    ops: alloc::vec::IntoIter<(K, Marked<D>)>,
+}
+
+impl<K, D> Iterator for IntoIter<K, D> {
+    type Item = (K, SeqMarked<D>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (k, m) = self.ops.next()?;
+        let v = SeqMarked::new(*self.next_seq, m);
+        self.next_seq += 1;
+        Some((k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ops.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InternalSeq;
+    use crate::SeqMarked;
+    use crate::WriteBatch;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    #[test]
+    fn test_iter_and_get() {
+        let mut b = WriteBatch::new();
+        b.put(1, "a");
+        b.delete(2);
+        b.put(2, "b");
+        b.delete(1);
+        let c = b.commit(InternalSeq::new(7));
+
+        assert_eq!(c.len(), 4);
+        assert_eq!(c.seq_range().start(), InternalSeq::new(7));
+        assert_eq!(c.seq_range().last(), Some(InternalSeq::new(10)));
+
+        assert_eq!(c.iter().collect::<Vec<_>>(), vec![
+            (&1, norm(7, &"a")),
+            (&2, ts(8)),
+            (&2, norm(9, &"b")),
+            (&1, ts(10)),
+        ]);
+
+        assert_eq!(c.get(&1), Some(ts(10)));
+        assert_eq!(c.get(&2), Some(SeqMarked::new_normal(9, &"b")));
+        assert_eq!(c.get(&3), None);
+
+        let entries = c.into_iter().collect::<Vec<_>>();
+        assert_eq!(entries[2], (2, norm(9, "b")));
+        assert_eq!(entries[3], (1, ts(10)));
+    }
+}
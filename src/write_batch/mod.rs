@@ -0,0 +1,194 @@
+//! A batch of writes applied atomically with a contiguous range of seqs.
+
+mod codec;
+mod committed_batch;
+
+use alloc::vec::Vec;
+
+pub use committed_batch::CommittedBatch;
+
+use crate::InternalSeq;
+use crate::Marked;
+#[cfg(feature = "std")]
+use crate::SeqAllocator;
+
+/// A batch of puts and deletes, to be assigned a contiguous range of seqs on commit.
+///
+/// The i-th operation is assigned seq `first_seq + i`, thus a later operation on the same key
+/// shadows an earlier one.
+///
+/// ```rust
+/// use seq_marked::InternalSeq;
+/// use seq_marked::SeqMarked;
+/// use seq_marked::WriteBatch;
+///
+/// let mut batch = WriteBatch::new();
+/// batch.put("a", "v1");
+/// batch.delete("b");
+/// batch.put("a", "v2");
+///
+/// let committed = batch.commit(InternalSeq::new(10));
+/// assert_eq!(committed.get(&"a"), Some(SeqMarked::new_normal(12, &"v2")));
+///
+/// let entries = committed.into_iter().collect::<Vec<_>>();
+/// assert_eq!(entries, vec![
+///     ("a", SeqMarked::new_normal(10, "v1")),
+///     ("b", SeqMarked::new_tombstone(11)),
+///     ("a", SeqMarked::new_normal(12, "v2")),
+/// ]);
+/// ```
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq)]
+pub struct WriteBatch<K, D = Vec<u8>> {
+    ops: Vec<(K, Marked<D>)>,
+}
+
+impl<K, D> Default for WriteBatch<K, D> {
+    fn default() -> Self {
+        Self { ops: Vec::new() }
+    }
+}
+
+impl<K, D> WriteBatch<K, D> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a write of `data` to `key`.
+    pub fn put(&mut self, key: K, data: D) {
+        self.ops.push((key, Marked::Normal(data)));
+    }
+
+    /// Appends a delete of `key`, which writes a tombstone.
+    pub fn delete(&mut self, key: K) {
+        self.ops.push((key, Marked::TombStone));
+    }
+
+    /// Returns the number of operations, i.e., the number of seqs required to commit it.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Returns the operations in the order they are appended.
+    pub fn ops(&self) -> &[(K, Marked<D>)] {
+        &self.ops
+    }
+
+    /// Assigns seqs starting from `first_seq` to the operations.
+    ///
+    /// The caller is responsible for allocating the seqs `[first_seq, first_seq + len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `first_seq + len` overflows `u64`.
+    pub fn commit(self, first_seq: InternalSeq) -> CommittedBatch<K, D> {
+        assert!(
+            first_seq.checked_add(self.ops.len() as u64).is_some(),
+            "seq overflow: {} operations from seq {}",
+            self.ops.len(),
+            *first_seq
+        );
+        CommittedBatch::new(first_seq, self.ops)
+    }
+
+    /// Reserves seqs from `allocator` and assigns them to the operations.
+    ///
+    /// The reserved range, [`CommittedBatch::seq_range()`], must be published to `allocator`
+    /// after the batch is applied.
+    #[cfg(feature = "std")]
+    pub fn commit_with(self, allocator: &SeqAllocator) -> CommittedBatch<K, D> {
+        let range = allocator.reserve(self.ops.len() as u64);
+        self.commit(range.start())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_batch() {
+        let mut b = WriteBatch::new();
+        assert!(b.is_empty());
+
+        b.put(1, "a");
+        b.delete(2);
+        assert_eq!(b.len(), 2);
+        assert_eq!(b.ops(), &[(1, Marked::Normal("a")), (2, Marked::TombStone)]);
+    }
+
+    #[test]
+    fn test_commit_empty() {
+        let c = WriteBatch::<u64, u64>::new().commit(InternalSeq::new(5));
+        assert!(c.is_empty());
+        assert!(c.seq_range().is_empty());
+        assert_eq!(c.into_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_commit_at_max_seq() {
+        let mut b = WriteBatch::new();
+        b.put(1, "a");
+        b.put(2, "b");
+
+        let c = b.commit(InternalSeq::new(u64::MAX - 2));
+        assert_eq!(c.seq_range().last(), Some(InternalSeq::new(u64::MAX - 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "seq overflow: 2 operations from seq 18446744073709551614")]
+    fn test_commit_seq_overflow() {
+        let mut b = WriteBatch::new();
+        b.put(1, "a");
+        b.put(2, "b");
+
+        b.commit(InternalSeq::new(u64::MAX - 1));
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests_std {
+    use super::*;
+    use crate::SeqRange;
+    use crate::VersionedMap;
+    use crate::testing::norm;
+    use crate::testing::ts;
+
+    #[test]
+    fn test_commit_with() {
+        let alloc = SeqAllocator::new(InternalSeq::new(10));
+
+        let mut b = WriteBatch::new();
+        b.put(1, "a");
+        b.put(2, "b");
+        let c1 = b.commit_with(&alloc);
+
+        let mut b = WriteBatch::new();
+        b.delete(1);
+        let c2 = b.commit_with(&alloc);
+
+        let r1 = SeqRange::new(InternalSeq::new(11), InternalSeq::new(13));
+        assert_eq!(c1.seq_range(), r1);
+        assert_eq!(c2.seq_range().start(), InternalSeq::new(13));
+
+        // Apply to a memtable, then publish.
+        let mut m = VersionedMap::new();
+        for (k, v) in c2.clone().into_iter().chain(c1.clone()) {
+            m.put(k, v);
+        }
+        alloc.publish(c2.seq_range());
+        alloc.publish(c1.seq_range());
+        assert_eq!(alloc.last_published(), InternalSeq::new(13));
+
+        let snapshot = alloc.last_published();
+        assert_eq!(m.get_at(&1, snapshot), Some(&ts(13)));
+        assert_eq!(m.get_at(&2, snapshot), Some(&norm(12, "b")));
+        assert_eq!(m.get_at(&1, InternalSeq::new(12)), Some(&norm(11, "a")));
+    }
+}